@@ -0,0 +1,309 @@
+//! Distance Sensor Calibration
+//!
+//! Fits the mounting offset and beam angle of each [`WallDistanceSensor`]
+//! from readings taken at known robot poses.
+//!
+//! For every sample the residual is the same quantity returned by
+//! [`WallDistanceSensor::measurement_error`]:
+//!
+//!     residual = measured - predicted
+//!
+//! The offset `(x, y)` and angle of each sensor are found with a damped
+//! Gauss-Newton least squares solve, starting from the values the sensor was
+//! constructed with. A single reading only constrains the offset along the
+//! beam, so samples should be taken at several headings, either by placing
+//! the robot at known poses against the walls or by spinning it in place at
+//! a known position near a corner.
+
+use std::time::Duration;
+
+use log::{info, warn};
+use vexide::math::Angle;
+
+use super::CalibrationError;
+use crate::{
+    clock::{self, sleep},
+    hardware::wall_distance_sensor::{WallDistanceSensor, raycast},
+    localization::{pose::Pose, rcl::RclParameters, vec2::Vec2},
+    subsystems::drivetrain::Drivetrain,
+    utils::solve_linear,
+};
+
+/// A single distance reading taken at a known pose.
+#[derive(Clone, Copy)]
+struct Sample {
+    pose: Pose,
+    measured: f64,
+}
+
+/// Fitted mounting of a single distance sensor.
+#[derive(Debug, Clone, Copy)]
+pub struct SensorFit {
+    /// Sensor position relative to the tracking center.
+    pub offset: Vec2<f64>,
+
+    /// Beam direction relative to the robot frame.
+    pub angle: Angle,
+
+    /// Root mean square residual after fitting.
+    pub rms: f64,
+
+    /// Number of samples used in the fit.
+    pub samples: usize,
+}
+
+/// Collects distance readings at known poses and fits each sensor's mounting.
+pub struct DistanceSensorCalibration {
+    /// Samples collected for each sensor, in sensor order.
+    samples: Vec<Vec<Sample>>,
+
+    /// Field geometry and gating used to predict wall hits.
+    params: RclParameters,
+}
+
+impl DistanceSensorCalibration {
+    /// Number of readings averaged by [`Self::record`].
+    const READINGS: usize = 10;
+
+    /// Maximum number of Gauss-Newton iterations.
+    const ITERATIONS: usize = 25;
+
+    /// Step used for the finite difference Jacobian.
+    const STEP: f64 = 1e-4;
+
+    /// Damping added to the normal equations so poorly observed
+    /// parameters stay near their initial values.
+    const DAMPING: f64 = 1e-3;
+
+    /// Creates an empty calibration for `sensor_count` sensors.
+    pub fn new(sensor_count: usize, params: RclParameters) -> Self {
+        Self {
+            samples: vec![Vec::new(); sensor_count],
+            params,
+        }
+    }
+
+    /// Records averaged readings from every sensor with the robot
+    /// stationary at a known `pose`.
+    ///
+    /// Returns [`CalibrationError::Disconnected`] without recording anything
+    /// if a sensor cannot be read.
+    pub async fn record(
+        &mut self,
+        sensors: &[WallDistanceSensor],
+        pose: Pose,
+    ) -> Result<(), CalibrationError> {
+        let mut sums = vec![(0.0, 0); sensors.len()];
+
+        for _ in 0..Self::READINGS {
+            for (i, (sum, sensor)) in sums.iter_mut().zip(sensors).enumerate() {
+                match sensor.distance() {
+                    Ok(Some(distance)) => {
+                        sum.0 += distance;
+                        sum.1 += 1;
+                    }
+                    Ok(None) => (),
+                    Err(_) => {
+                        warn!("sensor {i}: disconnected");
+                        return Err(CalibrationError::Disconnected);
+                    }
+                }
+            }
+
+            sleep(Duration::from_millis(50)).await;
+        }
+
+        for (i, (sum, count)) in sums.into_iter().enumerate() {
+            if count == 0 {
+                warn!("sensor {i}: no reading at {pose}");
+                continue;
+            }
+
+            self.samples[i].push(Sample {
+                pose,
+                measured: sum / count as f64,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Slowly spins the robot in place at a known `position`, recording a
+    /// reading from every sensor as it turns.
+    ///
+    /// The heading of each sample comes from odometry, so the IMU should be
+    /// calibrated first. Keep `speed` low; readings are not latency corrected.
+    ///
+    /// The robot is stopped and an error returned if it has not turned
+    /// `turns` within `timeout`, or if a sensor cannot be read. Samples
+    /// recorded before then are kept.
+    pub async fn record_spin(
+        &mut self,
+        sensors: &[WallDistanceSensor],
+        drivetrain: &mut Drivetrain,
+        position: Vec2<f64>,
+        turns: f64,
+        speed: f64,
+        timeout: Duration,
+    ) -> Result<(), CalibrationError> {
+        let start = drivetrain.pose().h;
+        let start_time = clock::now();
+
+        let result = loop {
+            let heading = drivetrain.pose().h;
+            if (heading - start).abs().as_turns() >= turns {
+                break Ok(());
+            }
+
+            if clock::elapsed(start_time) > timeout {
+                warn!(
+                    "spin timed out after {:.2} of {turns} turns",
+                    (heading - start).abs().as_turns()
+                );
+                break Err(CalibrationError::Timeout);
+            }
+
+            drivetrain.set_arcade(0.0, speed);
+
+            let pose = Pose::new(position.x, position.y, heading);
+            let mut disconnected = None;
+            for (i, (samples, sensor)) in self.samples.iter_mut().zip(sensors).enumerate() {
+                match sensor.distance() {
+                    Ok(Some(measured)) => samples.push(Sample { pose, measured }),
+                    Ok(None) => (),
+                    Err(_) => disconnected = Some(i),
+                }
+            }
+
+            if let Some(i) = disconnected {
+                warn!("sensor {i}: disconnected");
+                break Err(CalibrationError::Disconnected);
+            }
+
+            sleep(Duration::from_millis(50)).await;
+        };
+
+        drivetrain.set_voltages(0.0, 0.0);
+        result
+    }
+
+    /// Fits the offset and angle of every sensor.
+    ///
+    /// Returns `None` for sensors without enough usable samples.
+    pub fn solve(&self, sensors: &[WallDistanceSensor]) -> Vec<Option<SensorFit>> {
+        sensors
+            .iter()
+            .zip(&self.samples)
+            .map(|(sensor, samples)| self.fit(sensor.offset(), sensor.angle(), samples))
+            .collect()
+    }
+
+    /// Fits every sensor and logs the results ready to paste into
+    /// `WallDistanceSensor::new`.
+    pub fn print(&self, sensors: &[WallDistanceSensor]) {
+        for (i, fit) in self.solve(sensors).into_iter().enumerate() {
+            match fit {
+                Some(fit) => info!(
+                    "sensor {i}: Vec2::new({:.3}, {:.3}), Angle::from_degrees({:.2}) (rms {:.3} over {} samples)",
+                    fit.offset.x,
+                    fit.offset.y,
+                    fit.angle.as_degrees(),
+                    fit.rms,
+                    fit.samples,
+                ),
+                None => warn!("sensor {i}: not enough samples to fit"),
+            }
+        }
+    }
+
+    /// Residual of a sample for the parameters `[x, y, angle]`.
+    fn residual(&self, p: [f64; 3], sample: &Sample) -> Option<f64> {
+        let origin = sample.pose.to_field(Vec2::new(p[0], p[1]));
+        let direction = sample.pose.h + Angle::from_radians(p[2]);
+
+        raycast(
+            origin,
+            direction,
+            self.params.field_size,
+            self.params.max_raycast_dist,
+        )
+        .map(|hit| sample.measured - hit.distance)
+    }
+
+    /// Residual and its gradient with respect to `[x, y, angle]`.
+    fn linearize(&self, p: [f64; 3], sample: &Sample) -> Option<(f64, [f64; 3])> {
+        let r = self.residual(p, sample)?;
+        let mut gradient = [0.0; 3];
+
+        for (k, g) in gradient.iter_mut().enumerate() {
+            let mut stepped = p;
+            stepped[k] += Self::STEP;
+            *g = (self.residual(stepped, sample)? - r) / Self::STEP;
+        }
+
+        Some((r, gradient))
+    }
+
+    fn fit(&self, offset: Vec2<f64>, angle: Angle, samples: &[Sample]) -> Option<SensorFit> {
+        let mut p = [offset.x, offset.y, angle.as_radians()];
+
+        // Ignore readings that clearly hit something other than the predicted wall
+        let samples: Vec<&Sample> = samples
+            .iter()
+            .filter(|sample| {
+                self.residual(p, sample)
+                    .is_some_and(|r| r.abs() <= self.params.max_error)
+            })
+            .collect();
+
+        if samples.len() < 3 {
+            return None;
+        }
+
+        for _ in 0..Self::ITERATIONS {
+            let mut jtj = [[0.0; 3]; 3];
+            let mut jtr = [0.0; 3];
+
+            for (r, j) in samples
+                .iter()
+                .filter_map(|sample| self.linearize(p, sample))
+            {
+                for row in 0..3 {
+                    for col in 0..3 {
+                        jtj[row][col] += j[row] * j[col];
+                    }
+                    jtr[row] -= j[row] * r;
+                }
+            }
+
+            for (k, row) in jtj.iter_mut().enumerate() {
+                row[k] += Self::DAMPING;
+            }
+
+            let Some(delta) = solve_linear(jtj, jtr) else {
+                break;
+            };
+
+            for (value, step) in p.iter_mut().zip(delta) {
+                *value += step;
+            }
+
+            if delta.iter().all(|step| step.abs() < 1e-6) {
+                break;
+            }
+        }
+
+        let residuals: Vec<f64> = samples
+            .iter()
+            .filter_map(|sample| self.residual(p, sample))
+            .collect();
+        let rms = (residuals.iter().map(|r| r * r).sum::<f64>() / residuals.len() as f64).sqrt();
+
+        Some(SensorFit {
+            offset: Vec2::new(p[0], p[1]),
+            angle: Angle::from_radians(p[2]),
+            rms,
+            samples: residuals.len(),
+        })
+    }
+}
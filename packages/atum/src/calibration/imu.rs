@@ -0,0 +1,217 @@
+//! IMU Calibration
+//!
+//! Measures the drift and scale error of each inertial sensor in an
+//! [`Imu`] and produces the [`ImuCalibration`] used to correct and weight
+//! it.
+//!
+//! The routine has two parts:
+//!
+//! - **Stationary** – the robot sits still while each sensor is sampled.
+//!   A line fit through the samples gives the drift rate, and the scatter
+//!   around the line gives the noise.
+//! - **Turns** – the robot is turned an exact number of full turns and
+//!   squared back up against a reference (e.g. a field wall). The ratio of
+//!   the true rotation to each sensor's drift-corrected rotation is its
+//!   scale factor.
+//!
+//! Each sensor's variance is its noise plus the heading error its drift
+//! would accumulate over a skills run, so sensors that drift less are
+//! trusted more.
+
+use std::{f64::consts::TAU, time::Duration};
+
+use log::{info, warn};
+
+use super::CalibrationError;
+use crate::{
+    clock::{self, sleep},
+    hardware::imu::{Imu, ImuCalibration},
+};
+
+/// Stationary drift and noise of a single sensor.
+#[derive(Debug, Clone, Copy, Default)]
+struct Stationary {
+    /// Drift rate in radians per second.
+    drift: f64,
+
+    /// Variance of the samples around the drift line (radians²).
+    noise: f64,
+}
+
+/// Records stationary and turning measurements and fits per-sensor
+/// scale factors and variances.
+#[derive(Default)]
+pub struct ImuScaleCalibration {
+    /// Drift and noise of each sensor.
+    stationary: Vec<Stationary>,
+
+    /// Scale factor of each sensor.
+    scales: Vec<f64>,
+}
+
+impl ImuScaleCalibration {
+    /// Duration the drift of each sensor is assumed to accumulate over
+    /// when estimating its variance, matching a skills run.
+    const HORIZON: f64 = 60.0;
+
+    /// Creates an empty calibration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Samples every sensor for `duration` with the robot stationary.
+    ///
+    /// Returns [`CalibrationError::Disconnected`] without recording anything
+    /// if a sensor cannot be read.
+    pub async fn stationary(
+        &mut self,
+        imu: &Imu,
+        duration: Duration,
+    ) -> Result<(), CalibrationError> {
+        let start = clock::now();
+        let mut samples: Vec<Vec<(f64, f64)>> = Vec::new();
+
+        while clock::elapsed(start) < duration {
+            let t = clock::elapsed(start).as_secs_f64();
+            let rotations = imu.raw_rotations();
+            samples.resize(rotations.len(), Vec::new());
+
+            for (i, (sensor, rotation)) in samples.iter_mut().zip(rotations).enumerate() {
+                let Some(rotation) = rotation else {
+                    warn!("IMU {i}: disconnected");
+                    return Err(CalibrationError::Disconnected);
+                };
+                sensor.push((t, rotation.as_radians()));
+            }
+
+            sleep(Duration::from_millis(10)).await;
+        }
+
+        self.stationary = samples.iter().map(|sensor| fit_line(sensor)).collect();
+        Ok(())
+    }
+
+    /// Records the rotation of every sensor while the robot is turned
+    /// exactly `turns` full turns.
+    ///
+    /// The robot should start and finish squared against the same
+    /// reference, and `done` should return `true` once it has been turned
+    /// back into place (e.g. when a controller button is pressed).
+    ///
+    /// Nothing is recorded if `done` does not return `true` within
+    /// `timeout`, or if a sensor disconnects.
+    pub async fn turns<F>(
+        &mut self,
+        imu: &Imu,
+        turns: f64,
+        timeout: Duration,
+        mut done: F,
+    ) -> Result<(), CalibrationError>
+    where
+        F: FnMut() -> bool,
+    {
+        let start = clock::now();
+        let before = imu.raw_rotations();
+
+        while !done() {
+            if let Some(i) = imu.raw_rotations().iter().position(Option::is_none) {
+                warn!("IMU {i}: disconnected");
+                return Err(CalibrationError::Disconnected);
+            }
+
+            if clock::elapsed(start) > timeout {
+                warn!("turns timed out");
+                return Err(CalibrationError::Timeout);
+            }
+
+            sleep(Duration::from_millis(10)).await;
+        }
+
+        let elapsed = clock::elapsed(start).as_secs_f64();
+        let after = imu.raw_rotations();
+
+        self.scales = before
+            .iter()
+            .zip(&after)
+            .enumerate()
+            .map(|(i, (before, after))| {
+                let (Some(before), Some(after)) = (before, after) else {
+                    warn!("IMU {i}: missing reading, keeping unit scale");
+                    return 1.0;
+                };
+
+                let drift = self.stationary.get(i).map_or(0.0, |s| s.drift);
+                let measured = (*after - *before).as_radians() - drift * elapsed;
+
+                if measured.abs() < 1e-3 {
+                    warn!("IMU {i}: no rotation measured, keeping unit scale");
+                    return 1.0;
+                }
+
+                turns * TAU / measured.abs()
+            })
+            .collect();
+
+        Ok(())
+    }
+
+    /// Returns the fitted calibration of every sensor.
+    pub fn solve(&self) -> Vec<ImuCalibration> {
+        let count = self.stationary.len().max(self.scales.len());
+
+        (0..count)
+            .map(|i| {
+                let stationary = self.stationary.get(i).copied().unwrap_or_default();
+                let scale = self.scales.get(i).copied().unwrap_or(1.0);
+                let drift_error = stationary.drift * scale * Self::HORIZON;
+
+                ImuCalibration {
+                    scale,
+                    variance: stationary.noise + drift_error * drift_error,
+                }
+            })
+            .collect()
+    }
+
+    /// Logs the fitted calibration of every sensor ready to paste into
+    /// `Imu::set_calibration`.
+    pub fn print(&self) {
+        for (i, calibration) in self.solve().into_iter().enumerate() {
+            let drift = self.stationary.get(i).map_or(0.0, |s| s.drift);
+
+            info!(
+                "IMU {i}: ImuCalibration {{ scale: {:.10}, variance: {:e} }} (drift {:.4} deg/s)",
+                calibration.scale,
+                calibration.variance,
+                drift.to_degrees(),
+            );
+        }
+    }
+}
+
+/// Fits `y = a + b t` by least squares and returns the slope and the
+/// variance of the residuals.
+fn fit_line(samples: &[(f64, f64)]) -> Stationary {
+    let n = samples.len() as f64;
+    if samples.len() < 2 {
+        return Stationary::default();
+    }
+
+    let mean_t = samples.iter().map(|(t, _)| t).sum::<f64>() / n;
+    let mean_y = samples.iter().map(|(_, y)| y).sum::<f64>() / n;
+
+    let covariance: f64 = samples
+        .iter()
+        .map(|(t, y)| (t - mean_t) * (y - mean_y))
+        .sum();
+    let spread: f64 = samples.iter().map(|(t, _)| (t - mean_t).powi(2)).sum();
+
+    let drift = if spread > 0.0 { covariance / spread } else { 0.0 };
+    let noise = samples
+        .iter()
+        .map(|(t, y)| (y - mean_y - drift * (t - mean_t)).powi(2))
+        .sum::<f64>()
+        / n;
+
+    Stationary { drift, noise }
+}
@@ -0,0 +1,26 @@
+//! Calibration routines
+//!
+//! Routines that measure physical constants of the robot instead of
+//! relying on hand-measured values. Each routine logs the fitted values
+//! in a form that can be pasted directly into a robot's `main.rs`.
+//!
+//! - [`distance_sensor`] – mounting offset and angle of wall distance sensors
+//! - [`imu`] – scale factor and variance of inertial sensors
+//! - [`tracking_wheel`] – diameter and offset of tracking wheels
+//!
+//! Routines that wait on the robot or the user stop after a timeout, and
+//! stop early if a device they read disconnects, returning a
+//! [`CalibrationError`] instead of hanging.
+pub mod distance_sensor;
+pub mod imu;
+pub mod tracking_wheel;
+
+/// Reason a calibration routine stopped without recording its motion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalibrationError {
+    /// The motion did not finish before the timeout.
+    Timeout,
+
+    /// A device the routine reads could not be read.
+    Disconnected,
+}
@@ -0,0 +1,247 @@
+//! Tracking Wheel Calibration
+//!
+//! Solves for the effective diameter and mounting offset of each
+//! [`TrackingWheel`] from two motions:
+//!
+//! - spinning in place a number of full turns, measured by the IMU
+//! - driving a known straight distance forward
+//!
+//! A wheel with measurement axis `a` and offset `o` from the center of
+//! rotation travels
+//!
+//!     s = d · a + dθ (o × a)
+//!
+//! for a robot-frame translation `d` and rotation `dθ`. The spin isolates
+//! the rotational term and the straight drive isolates the translational
+//! term, which together give the diameter and the component of the offset
+//! perpendicular to the wheel. The component along the wheel does not
+//! affect odometry, so it is kept from the configured offset.
+//!
+//! Run this before the wheels and IMU are handed to [`Odometry`].
+//!
+//! [`Odometry`]: crate::localization::odometry::Odometry
+
+use std::{f64::consts::PI, time::Duration};
+
+use log::{info, warn};
+
+use super::CalibrationError;
+use crate::{
+    clock::{self, sleep},
+    hardware::{imu::Imu, motor_group::MotorGroup, tracking_wheel::TrackingWheel},
+    localization::vec2::Vec2,
+};
+
+/// Wheel rotations and IMU rotation accumulated over one motion.
+#[derive(Clone)]
+struct Segment {
+    /// Encoder turns of each wheel.
+    turns: Vec<f64>,
+
+    /// Robot rotation in radians.
+    rotation: f64,
+}
+
+impl Segment {
+    fn snapshot(wheels: &[TrackingWheel], imu: &mut Imu) -> Self {
+        Self {
+            turns: wheels.iter().map(|wheel| wheel.position().as_turns()).collect(),
+            rotation: imu.rotation().as_radians(),
+        }
+    }
+
+    fn since(&self, start: &Segment) -> Self {
+        Self {
+            turns: self
+                .turns
+                .iter()
+                .zip(&start.turns)
+                .map(|(end, start)| end - start)
+                .collect(),
+            rotation: self.rotation - start.rotation,
+        }
+    }
+}
+
+/// Fitted geometry of a single tracking wheel.
+#[derive(Debug, Clone, Copy)]
+pub struct WheelFit {
+    /// Effective wheel diameter.
+    pub diameter: f64,
+
+    /// Wheel position relative to the robot's center of rotation.
+    pub from_center: Vec2<f64>,
+}
+
+/// Records calibration motions and fits tracking wheel geometry.
+#[derive(Default)]
+pub struct TrackingWheelCalibration {
+    /// Totals from spinning in place.
+    spin: Option<Segment>,
+
+    /// Totals from driving straight, with the distance driven.
+    straight: Option<(Segment, f64)>,
+}
+
+impl TrackingWheelCalibration {
+    /// Time to let the robot come to rest before reading final totals.
+    const SETTLE: Duration = Duration::from_millis(500);
+
+    /// Longest the robot may take to spin each full turn.
+    const SPIN_TIMEOUT_PER_TURN: Duration = Duration::from_secs(5);
+
+    /// Creates an empty calibration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Spins the robot in place for `turns` full rotations at the given voltage.
+    ///
+    /// The robot is stopped and nothing is recorded if it takes longer than
+    /// `SPIN_TIMEOUT_PER_TURN` per turn, or if every IMU fails.
+    pub async fn spin(
+        &mut self,
+        wheels: &[TrackingWheel],
+        imu: &mut Imu,
+        left: &mut MotorGroup,
+        right: &mut MotorGroup,
+        turns: f64,
+        voltage: f64,
+    ) -> Result<(), CalibrationError> {
+        let start = Segment::snapshot(wheels, imu);
+        let start_time = clock::now();
+        let timeout = Self::SPIN_TIMEOUT_PER_TURN.mul_f64(turns.abs());
+
+        left.set_voltage(-voltage);
+        right.set_voltage(voltage);
+
+        let result = loop {
+            let Some(rotation) = imu.try_rotation() else {
+                warn!("IMU disconnected during spin");
+                break Err(CalibrationError::Disconnected);
+            };
+
+            let turned = (rotation.as_radians() - start.rotation).abs();
+            if turned >= turns * 2.0 * PI {
+                break Ok(());
+            }
+
+            if clock::elapsed(start_time) > timeout {
+                warn!(
+                    "spin timed out after {:.2} of {turns} turns",
+                    turned / (2.0 * PI)
+                );
+                break Err(CalibrationError::Timeout);
+            }
+
+            sleep(Duration::from_millis(10)).await;
+        };
+
+        left.set_voltage(0.0);
+        right.set_voltage(0.0);
+        result?;
+        sleep(Self::SETTLE).await;
+
+        self.spin = Some(Segment::snapshot(wheels, imu).since(&start));
+        Ok(())
+    }
+
+    /// Records a straight motion of exactly `distance` inches forward.
+    ///
+    /// The robot is pushed or driven along a measured line, and `done`
+    /// should return `true` once it has reached the end (e.g. when a
+    /// controller button is pressed). Nothing is recorded if `done` does not
+    /// return `true` within `timeout`.
+    pub async fn straight<F>(
+        &mut self,
+        wheels: &[TrackingWheel],
+        imu: &mut Imu,
+        distance: f64,
+        timeout: Duration,
+        mut done: F,
+    ) -> Result<(), CalibrationError>
+    where
+        F: FnMut() -> bool,
+    {
+        let start = Segment::snapshot(wheels, imu);
+        let start_time = clock::now();
+
+        while !done() {
+            if clock::elapsed(start_time) > timeout {
+                warn!("straight motion timed out");
+                return Err(CalibrationError::Timeout);
+            }
+
+            sleep(Duration::from_millis(10)).await;
+        }
+
+        sleep(Self::SETTLE).await;
+
+        self.straight = Some((Segment::snapshot(wheels, imu).since(&start), distance));
+        Ok(())
+    }
+
+    /// Fits the geometry of every wheel.
+    ///
+    /// Returns `None` for every wheel if the spin has not been recorded.
+    /// Without a straight motion, or for wheels nearly perpendicular to the
+    /// direction of travel, the configured diameter is kept.
+    pub fn solve(&self, wheels: &[TrackingWheel]) -> Vec<Option<WheelFit>> {
+        wheels
+            .iter()
+            .enumerate()
+            .map(|(i, wheel)| self.fit(i, wheel))
+            .collect()
+    }
+
+    /// Fits every wheel and logs the results ready to paste into
+    /// `TrackingWheel::new`.
+    pub fn print(&self, wheels: &[TrackingWheel]) {
+        for (i, fit) in self.solve(wheels).into_iter().enumerate() {
+            match fit {
+                Some(fit) => info!(
+                    "wheel {i}: {:.9}, Vec2::new({:.8}, {:.8})",
+                    fit.diameter, fit.from_center.x, fit.from_center.y,
+                ),
+                None => warn!("wheel {i}: spin in place before solving"),
+            }
+        }
+    }
+
+    fn fit(&self, i: usize, wheel: &TrackingWheel) -> Option<WheelFit> {
+        let spin = self.spin.as_ref()?;
+        if spin.rotation.abs() < 1e-3 {
+            return None;
+        }
+
+        let angle = wheel.angle().as_radians();
+        let axis = Vec2::new(angle.cos(), angle.sin());
+
+        // Wheel turns per radian of robot rotation
+        let turns_per_radian = spin.turns[i] / spin.rotation;
+
+        let diameter = match &self.straight {
+            Some((straight, distance)) if (distance * axis.x).abs() > 1.0 => {
+                // Remove any rotation that happened while driving straight
+                let turns = straight.turns[i] - straight.rotation * turns_per_radian;
+                distance * axis.x / (PI * turns)
+            }
+            _ => {
+                warn!("wheel {i}: no usable straight motion, keeping configured diameter");
+                wheel.diameter()
+            }
+        };
+
+        // Rotational lever arm `o × a` of the wheel
+        let lever = PI * diameter * turns_per_radian;
+
+        // Unit vector `n` with `n × a = 1`
+        let normal = Vec2::new(axis.y, -axis.x);
+        let from_center = axis * wheel.from_center().dot(axis) + normal * lever;
+
+        Some(WheelFit {
+            diameter,
+            from_center,
+        })
+    }
+}
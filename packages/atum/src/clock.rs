@@ -0,0 +1,92 @@
+//! Time Source
+//!
+//! Control loops read the time and sleep through this module instead of
+//! `Instant::now()` and `vexide::time::sleep` directly. On the robot it is a
+//! thin wrapper over both. In a [`simulation`](crate::simulation) it switches
+//! to a virtual clock that only moves when the simulation advances it, so
+//! motions run as fast as the host can step them and produce the same
+//! timing every run.
+
+use std::{
+    cell::Cell,
+    future::poll_fn,
+    task::Poll,
+    time::{Duration, Instant},
+};
+
+thread_local! {
+    /// Start instant and elapsed virtual time, when virtual time is in use.
+    static VIRTUAL: Cell<Option<(Instant, Duration)>> = const { Cell::new(None) };
+}
+
+/// Returns the current time.
+pub fn now() -> Instant {
+    match VIRTUAL.get() {
+        Some((start, elapsed)) => start + elapsed,
+        None => Instant::now(),
+    }
+}
+
+/// Returns the time elapsed since `earlier`.
+pub fn elapsed(earlier: Instant) -> Duration {
+    now().saturating_duration_since(earlier)
+}
+
+/// Waits for `duration` to pass.
+pub async fn sleep(duration: Duration) {
+    if !is_virtual() {
+        vexide::time::sleep(duration).await;
+        return;
+    }
+
+    let deadline = now() + duration;
+    poll_fn(|cx| {
+        if now() >= deadline {
+            Poll::Ready(())
+        } else {
+            // Ask to be polled again; the simulation advances the clock
+            // between polls
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    })
+    .await;
+}
+
+/// Lets every other task run once before continuing.
+pub async fn yield_now() {
+    let mut yielded = false;
+    poll_fn(|cx| {
+        if yielded {
+            Poll::Ready(())
+        } else {
+            yielded = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    })
+    .await;
+}
+
+/// Switches to a virtual clock that starts at the current time and only
+/// moves through [`advance`].
+pub fn use_virtual_time() {
+    VIRTUAL.set(Some((Instant::now(), Duration::ZERO)));
+}
+
+/// Switches back to real time.
+pub fn use_real_time() {
+    VIRTUAL.set(None);
+}
+
+/// Returns `true` if the virtual clock is in use.
+pub fn is_virtual() -> bool {
+    VIRTUAL.get().is_some()
+}
+
+/// Moves the virtual clock forward by `duration`. Does nothing in real time.
+pub fn advance(duration: Duration) {
+    if let Some((start, elapsed)) = VIRTUAL.get() {
+        VIRTUAL.set(Some((start, elapsed + duration)));
+    }
+}
@@ -0,0 +1,198 @@
+//! Battery Monitoring
+//!
+//! Motion controllers command a fraction of [`Motor::V5_MAX_VOLTAGE`], so the
+//! same command produces less torque on a drained battery than on a fresh
+//! one. [`Battery`] measures how far the battery is from its nominal voltage
+//! so motor groups can scale their voltage commands to make up for it, and
+//! warns when the battery runs low.
+//!
+//! [`Motor::V5_MAX_VOLTAGE`]: vexide::prelude::Motor::V5_MAX_VOLTAGE
+
+use std::{cell::Cell, rc::Rc};
+
+use log::{info, warn};
+use vexide::controller::Controller;
+
+use super::devices::{BatteryDevice, V5Battery};
+
+/// How voltage commands are compensated for the battery's charge.
+#[derive(Debug, Clone, Copy)]
+pub struct BatteryParameters {
+    /// Voltage the controllers were tuned at (V).
+    pub nominal_voltage: f64,
+
+    /// Smallest compensation scale, applied on a freshly charged battery.
+    pub min_scale: f64,
+
+    /// Largest compensation scale, applied on a drained battery.
+    pub max_scale: f64,
+
+    /// Voltage below which the battery is considered low (V).
+    pub low_voltage: f64,
+
+    /// Voltage the battery must recover above `low_voltage` before the low
+    /// warning is cleared, so sag under load does not toggle it (V).
+    pub hysteresis: f64,
+}
+
+impl Default for BatteryParameters {
+    fn default() -> Self {
+        Self {
+            nominal_voltage: 12.8,
+            min_scale: 0.9,
+            max_scale: 1.15,
+            low_voltage: 12.2,
+            hysteresis: 0.3,
+        }
+    }
+}
+
+/// A handle to the robot's battery.
+///
+/// Clones share the same device and low battery state, so one battery can be
+/// handed to several motor groups and warn only once.
+#[derive(Clone)]
+pub struct Battery {
+    device: Rc<dyn BatteryDevice>,
+    params: BatteryParameters,
+    low: Rc<Cell<bool>>,
+}
+
+impl Battery {
+    /// Creates a handle to the V5 battery with the default parameters.
+    pub fn new() -> Self {
+        Self::with_device(V5Battery, BatteryParameters::default())
+    }
+
+    /// Creates a handle to any battery device, e.g. a
+    /// [`MockBattery`](super::mock::MockBattery).
+    pub fn with_device<B: BatteryDevice + 'static>(device: B, params: BatteryParameters) -> Self {
+        Self {
+            device: Rc::new(device),
+            params,
+            low: Rc::new(Cell::new(false)),
+        }
+    }
+
+    /// Returns the compensation parameters.
+    pub fn params(&self) -> BatteryParameters {
+        self.params
+    }
+
+    /// Returns the voltage of the battery (V).
+    pub fn voltage(&self) -> Option<f64> {
+        self.device.voltage()
+    }
+
+    /// Returns the scale that makes a voltage command produce the same
+    /// output as it would at the nominal voltage.
+    ///
+    /// Returns `1.0` if the battery cannot be read.
+    pub fn compensation(&self) -> f64 {
+        match self.voltage() {
+            Some(voltage) if voltage > 0.0 => (self.params.nominal_voltage / voltage)
+                .clamp(self.params.min_scale, self.params.max_scale),
+            _ => 1.0,
+        }
+    }
+
+    /// Returns `true` if the battery was low at the last check.
+    pub fn is_low(&self) -> bool {
+        self.low.get()
+    }
+
+    /// Reads the battery, logging a warning when it runs low and a note when
+    /// it recovers. Returns whether the battery is low.
+    pub fn check(&self) -> bool {
+        let Some(voltage) = self.voltage() else {
+            return self.is_low();
+        };
+
+        let low = if self.is_low() {
+            voltage < self.params.low_voltage + self.params.hysteresis
+        } else {
+            voltage < self.params.low_voltage
+        };
+
+        if low != self.is_low() {
+            if low {
+                warn!("Battery low ({voltage:.1} V)");
+            } else {
+                info!("Battery recovered ({voltage:.1} V)");
+            }
+        }
+
+        self.low.set(low);
+        low
+    }
+
+    /// Checks the battery and, when it runs low, shows a warning on the third
+    /// line of the controller screen and rumbles the controller. Returns
+    /// whether the battery is low.
+    ///
+    /// The controller is only written when the battery first runs low, so
+    /// this can be called every iteration of the driver loop.
+    pub async fn warn_controller(&self, controller: &mut Controller) -> bool {
+        let was_low = self.is_low();
+        let low = self.check();
+
+        if low && !was_low {
+            let voltage = self.voltage().unwrap_or_default();
+            _ = controller
+                .set_text(format!("LOW BATTERY {voltage:.1}V"), 3, 1)
+                .await;
+            _ = controller.rumble("--").await;
+        }
+
+        low
+    }
+}
+
+impl Default for Battery {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hardware::mock::MockBattery;
+
+    #[test]
+    fn compensation_scales_by_nominal_over_actual() {
+        let device = MockBattery::new();
+        let battery = Battery::with_device(device.clone(), BatteryParameters::default());
+
+        device.set_voltage(Some(12.8));
+        assert!((battery.compensation() - 1.0).abs() < 1e-9);
+
+        device.set_voltage(Some(12.0));
+        assert!((battery.compensation() - 12.8 / 12.0).abs() < 1e-9);
+
+        // Clamped on very low or high readings, and neutral without one
+        device.set_voltage(Some(9.0));
+        assert_eq!(battery.compensation(), 1.15);
+        device.set_voltage(Some(15.0));
+        assert_eq!(battery.compensation(), 0.9);
+        device.set_voltage(None);
+        assert_eq!(battery.compensation(), 1.0);
+    }
+
+    #[test]
+    fn low_warning_has_hysteresis() {
+        let device = MockBattery::new();
+        let battery = Battery::with_device(device.clone(), BatteryParameters::default());
+        assert!(!battery.check());
+
+        device.set_voltage(Some(12.1));
+        assert!(battery.check());
+
+        // Recovering slightly under load does not clear the warning
+        device.set_voltage(Some(12.3));
+        assert!(battery.check());
+
+        device.set_voltage(Some(12.6));
+        assert!(!battery.check());
+    }
+}
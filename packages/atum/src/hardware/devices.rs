@@ -0,0 +1,301 @@
+//! Device Traits
+//!
+//! The hardware wrappers in this module talk to devices through these traits
+//! rather than to vexide types directly. vexide's devices implement them for
+//! use on the robot, and the [`mock`](super::mock) devices implement them so
+//! the library's logic can run on a host machine.
+
+use std::{cell::RefCell, future::Future, pin::Pin, rc::Rc};
+
+use log::warn;
+use vexide::{
+    math::Angle,
+    adi::digital::LogicLevel,
+    prelude::{AdiDigitalOut, DistanceSensor, InertialSensor, Motor},
+    smart::{distance::DistanceObjectError, motor::BrakeMode},
+};
+
+/// A motor with an integrated encoder.
+pub trait MotorDevice {
+    /// Sets the voltage applied to the motor.
+    fn set_voltage(&mut self, voltage: f64);
+
+    /// Sets the target velocity (RPM) of the motor's built-in controller.
+    fn set_velocity(&mut self, rpm: i32);
+
+    /// Sets the target position of the motor's built-in position
+    /// controller, moving at up to `rpm`.
+    fn set_position_target(&mut self, position: Angle, rpm: i32);
+
+    /// Stops the motor using the given brake mode.
+    fn brake(&mut self, mode: BrakeMode);
+
+    /// Limits the current the motor may draw (A).
+    fn set_current_limit(&mut self, limit: f64);
+
+    /// Returns the voltage applied to the motor.
+    fn voltage(&self) -> Option<f64>;
+
+    /// Returns the velocity of the motor (RPM).
+    fn velocity(&self) -> Option<f64>;
+
+    /// Returns the rotation measured by the motor's encoder.
+    fn position(&self) -> Option<Angle>;
+
+    /// Returns the temperature of the motor (°C).
+    fn temperature(&self) -> Option<f64>;
+
+    /// Returns the current drawn by the motor (A).
+    fn current(&self) -> Option<f64>;
+
+    /// Returns the efficiency of the motor (percent).
+    fn efficiency(&self) -> Option<f64>;
+
+    /// Returns the faults reported by the motor.
+    fn faults(&self) -> Option<MotorFaults>;
+}
+
+/// Faults reported by a motor.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MotorFaults {
+    /// The motor is too hot and is limiting its output.
+    pub over_temperature: bool,
+
+    /// The motor is drawing more than its current limit.
+    pub over_current: bool,
+
+    /// The motor's H-bridge has faulted.
+    pub driver_fault: bool,
+
+    /// The motor's H-bridge is drawing too much current.
+    pub driver_over_current: bool,
+}
+
+impl MotorFaults {
+    /// Returns `true` if any fault is set.
+    pub fn any(&self) -> bool {
+        self.over_temperature || self.over_current || self.driver_fault || self.driver_over_current
+    }
+}
+
+/// A digital output, e.g. the solenoid valve of a pneumatic cylinder.
+pub trait DigitalOutDevice {
+    /// Drives the output high or low.
+    fn set_level(&mut self, high: bool);
+}
+
+/// The robot's battery.
+pub trait BatteryDevice {
+    /// Returns the voltage of the battery (V).
+    fn voltage(&self) -> Option<f64>;
+}
+
+/// The V5 battery plugged into the brain.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct V5Battery;
+
+/// Future returned by [`ImuDevice::calibrate`], resolving to whether the
+/// calibration succeeded.
+pub type CalibrationFuture<'a> = Pin<Box<dyn Future<Output = bool> + 'a>>;
+
+/// An inertial sensor.
+///
+/// Angles are in the sensor's convention (clockwise positive); [`Imu`](super::imu::Imu)
+/// converts them to the robot's.
+pub trait ImuDevice {
+    /// Starts calibrating the sensor.
+    fn calibrate(&mut self) -> CalibrationFuture<'_>;
+
+    /// Returns the unbounded rotation of the sensor.
+    fn rotation(&self) -> Option<Angle>;
+
+    /// Returns the pitch of the sensor.
+    fn pitch(&self) -> Option<Angle>;
+
+    /// Sets the current rotation of the sensor.
+    fn set_rotation(&mut self, rotation: Angle);
+
+    /// Sets the current heading of the sensor.
+    fn set_heading(&mut self, heading: Angle);
+}
+
+/// An object detected by a distance sensor.
+#[derive(Debug, Clone, Copy)]
+pub struct DistanceReading {
+    /// Distance to the object (millimeters).
+    pub distance: u32,
+
+    /// Size of the object relative to the beam, if it could be measured.
+    pub relative_size: Option<u32>,
+}
+
+/// A distance sensor.
+pub trait DistanceDevice {
+    /// Returns the object in front of the sensor, if one is detected.
+    fn object(&self) -> Result<Option<DistanceReading>, DistanceObjectError>;
+}
+
+impl MotorDevice for Motor {
+    fn set_voltage(&mut self, voltage: f64) {
+        _ = Motor::set_voltage(self, voltage);
+    }
+
+    fn set_velocity(&mut self, rpm: i32) {
+        _ = Motor::set_velocity(self, rpm);
+    }
+
+    fn set_position_target(&mut self, position: Angle, rpm: i32) {
+        _ = Motor::set_position_target(self, position, rpm);
+    }
+
+    fn brake(&mut self, mode: BrakeMode) {
+        _ = Motor::brake(self, mode);
+    }
+
+    fn set_current_limit(&mut self, limit: f64) {
+        _ = Motor::set_current_limit(self, limit);
+    }
+
+    fn voltage(&self) -> Option<f64> {
+        Motor::voltage(self).ok()
+    }
+
+    fn velocity(&self) -> Option<f64> {
+        Motor::velocity(self).ok()
+    }
+
+    fn position(&self) -> Option<Angle> {
+        Motor::position(self).ok()
+    }
+
+    fn temperature(&self) -> Option<f64> {
+        Motor::temperature(self).ok()
+    }
+
+    fn current(&self) -> Option<f64> {
+        Motor::current(self).ok()
+    }
+
+    fn efficiency(&self) -> Option<f64> {
+        Motor::efficiency(self).ok()
+    }
+
+    fn faults(&self) -> Option<MotorFaults> {
+        Some(MotorFaults {
+            over_temperature: self.is_over_temperature().ok()?,
+            over_current: self.is_over_current().ok()?,
+            driver_fault: self.is_driver_fault().ok()?,
+            driver_over_current: self.is_driver_over_current().ok()?,
+        })
+    }
+}
+
+/// A motor shared between several owners, e.g. a motor group driving it and
+/// an encoder reading it.
+impl<M: MotorDevice + ?Sized> MotorDevice for Rc<RefCell<M>> {
+    fn set_voltage(&mut self, voltage: f64) {
+        self.borrow_mut().set_voltage(voltage);
+    }
+
+    fn set_velocity(&mut self, rpm: i32) {
+        self.borrow_mut().set_velocity(rpm);
+    }
+
+    fn set_position_target(&mut self, position: Angle, rpm: i32) {
+        self.borrow_mut().set_position_target(position, rpm);
+    }
+
+    fn brake(&mut self, mode: BrakeMode) {
+        self.borrow_mut().brake(mode);
+    }
+
+    fn set_current_limit(&mut self, limit: f64) {
+        self.borrow_mut().set_current_limit(limit);
+    }
+
+    fn voltage(&self) -> Option<f64> {
+        self.borrow().voltage()
+    }
+
+    fn velocity(&self) -> Option<f64> {
+        self.borrow().velocity()
+    }
+
+    fn position(&self) -> Option<Angle> {
+        self.borrow().position()
+    }
+
+    fn temperature(&self) -> Option<f64> {
+        self.borrow().temperature()
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.borrow().current()
+    }
+
+    fn efficiency(&self) -> Option<f64> {
+        self.borrow().efficiency()
+    }
+
+    fn faults(&self) -> Option<MotorFaults> {
+        self.borrow().faults()
+    }
+}
+
+impl DigitalOutDevice for AdiDigitalOut {
+    fn set_level(&mut self, high: bool) {
+        let level = if high {
+            LogicLevel::High
+        } else {
+            LogicLevel::Low
+        };
+        _ = AdiDigitalOut::set_level(self, level);
+    }
+}
+
+impl BatteryDevice for V5Battery {
+    fn voltage(&self) -> Option<f64> {
+        // The brain reports zero when it cannot read the battery
+        let voltage = vexide::battery::voltage();
+        (voltage > 0.0).then_some(voltage)
+    }
+}
+
+impl ImuDevice for InertialSensor {
+    fn calibrate(&mut self) -> CalibrationFuture<'_> {
+        Box::pin(async move {
+            match InertialSensor::calibrate(self).await {
+                Ok(_) => true,
+                Err(e) => {
+                    warn!("IMU calibration error: {:?}", e);
+                    false
+                }
+            }
+        })
+    }
+
+    fn rotation(&self) -> Option<Angle> {
+        InertialSensor::rotation(self).ok()
+    }
+
+    fn pitch(&self) -> Option<Angle> {
+        self.euler().ok().map(|euler| euler.a)
+    }
+
+    fn set_rotation(&mut self, rotation: Angle) {
+        _ = InertialSensor::set_rotation(self, rotation);
+    }
+
+    fn set_heading(&mut self, heading: Angle) {
+        _ = InertialSensor::set_heading(self, heading);
+    }
+}
+
+impl DistanceDevice for DistanceSensor {
+    fn object(&self) -> Result<Option<DistanceReading>, DistanceObjectError> {
+        Ok(DistanceSensor::object(self)?.map(|object| DistanceReading {
+            distance: object.distance,
+            relative_size: object.relative_size,
+        }))
+    }
+}
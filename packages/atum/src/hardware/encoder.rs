@@ -0,0 +1,100 @@
+use std::{cell::RefCell, rc::Rc};
+
+use vexide::{
+    math::Angle,
+    prelude::{AdiEncoder, RotationSensor},
+};
+
+use super::{average, devices::MotorDevice, motor_group::MotorGroup};
+
+/// A sensor that measures the rotation of a shaft.
+///
+/// Lets a [`TrackingWheel`](super::tracking_wheel::TrackingWheel) read from
+/// an ADI encoder, a smart-port rotation sensor or a motor's integrated
+/// encoder interchangeably.
+pub trait Encoder {
+    /// Returns the rotation of the measured shaft, or `None` if the sensor
+    /// could not be read.
+    fn position(&self) -> Option<Angle>;
+}
+
+impl Encoder for AdiEncoder<4096> {
+    fn position(&self) -> Option<Angle> {
+        AdiEncoder::position(self).ok()
+    }
+}
+
+impl Encoder for RotationSensor {
+    fn position(&self) -> Option<Angle> {
+        RotationSensor::position(self).ok()
+    }
+}
+
+/// The integrated encoders of a motor group geared to the measured shaft.
+///
+/// The group keeps driving its motors; the encoder only reads them, so a
+/// drive motor can track its wheel while the drivetrain uses it. Reading a
+/// drive motor this way only measures the wheel well while it is not
+/// slipping, so prefer a dedicated tracking wheel where one fits.
+pub struct MotorEncoder {
+    motors: Vec<Rc<RefCell<dyn MotorDevice>>>,
+
+    /// Rotations of the measured shaft per rotation of the motors.
+    ratio: f64,
+}
+
+impl MotorEncoder {
+    /// Creates an encoder from the motors of `group` turning the shaft at
+    /// `ratio` (output rotations per motor rotation).
+    pub fn new(group: &MotorGroup, ratio: f64) -> Self {
+        Self {
+            motors: group.shared_motors(),
+            ratio,
+        }
+    }
+}
+
+impl Encoder for MotorEncoder {
+    /// Returns the average rotation of the motors that could be read,
+    /// through the ratio.
+    fn position(&self) -> Option<Angle> {
+        let positions: Vec<f64> = self
+            .motors
+            .iter()
+            .filter_map(|motor| motor.borrow().position())
+            .map(|position| position.as_radians())
+            .collect();
+
+        if positions.is_empty() {
+            return None;
+        }
+
+        Some(Angle::from_radians(average(&positions) * self.ratio))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hardware::mock::MockMotor;
+
+    #[test]
+    fn motor_encoder_reads_a_driven_group() {
+        let motors = [MockMotor::new(), MockMotor::new()];
+        let mut group = MotorGroup::new(motors.to_vec(), None);
+        let encoder = MotorEncoder::new(&group, 0.5);
+
+        motors[0].update(|state| state.position = Angle::from_degrees(100.0));
+        motors[1].update(|state| state.position = Angle::from_degrees(140.0));
+        assert!((encoder.position().unwrap().as_degrees() - 60.0).abs() < 1e-9);
+
+        // The group still drives the motors the encoder reads
+        group.set_voltage(6.0);
+        assert!(motors.iter().all(|motor| motor.state().voltage == 6.0));
+
+        for motor in &motors {
+            motor.update(|state| state.connected = false);
+        }
+        assert_eq!(encoder.position(), None);
+    }
+}
@@ -1,37 +1,269 @@
-use std::f64::consts::TAU;
+use std::{
+    f64::consts::TAU,
+    future::poll_fn,
+    task::Poll,
+    time::{Duration, Instant},
+};
 
-use log::{debug, error, info};
-use vexide::{math::Angle, prelude::InertialSensor};
+use log::{debug, error, info, warn};
+use vexide::math::Angle;
 
-use super::average;
+use super::devices::ImuDevice;
+use crate::clock::{self, sleep};
+
+/// Per-sensor correction applied by [`Imu`].
+///
+/// Values are measured with
+/// [`ImuScaleCalibration`](crate::calibration::imu::ImuScaleCalibration).
+#[derive(Debug, Clone, Copy)]
+pub struct ImuCalibration {
+    /// Factor applied to the sensor's rotation to correct scale error.
+    pub scale: f64,
+
+    /// Estimated heading variance (radians²). Sensors are weighted by the
+    /// inverse of this value when combined.
+    pub variance: f64,
+}
+
+impl Default for ImuCalibration {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            variance: 1.0,
+        }
+    }
+}
+
+/// Health of a single inertial sensor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImuHealth {
+    /// The sensor is used for the combined rotation.
+    Healthy,
+
+    /// The sensor could not be read.
+    Disconnected,
+
+    /// The sensor can be read again after being disconnected. It may have
+    /// restarted and lost its rotation, so it is excluded until the
+    /// rotation is reset.
+    Reconnected,
+
+    /// The sensor's rotation changed faster than the robot can turn.
+    /// Excluded until the rotation is reset.
+    Jumped,
+
+    /// The sensor's rotation disagrees with the other sensors.
+    /// Excluded until the rotation is reset.
+    Disagreeing,
+
+    /// The sensor failed to calibrate. Excluded until it is calibrated.
+    Uncalibrated,
+}
+
+impl ImuHealth {
+    /// Whether the sensor stays excluded until the rotation is reset.
+    fn is_latched(&self) -> bool {
+        matches!(
+            self,
+            ImuHealth::Reconnected
+                | ImuHealth::Jumped
+                | ImuHealth::Disagreeing
+                | ImuHealth::Uncalibrated
+        )
+    }
+}
+
+/// Outcome of calibrating a single inertial sensor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalibrationStatus {
+    /// The sensor calibrated after the given number of attempts.
+    Calibrated(usize),
+
+    /// Every attempt returned an error.
+    Failed,
+
+    /// The last attempt did not finish before the timeout.
+    TimedOut,
+}
+
+/// Fault tracking state of a single inertial sensor.
+#[derive(Clone, Copy)]
+struct SensorState {
+    health: ImuHealth,
+
+    /// Time and scaled rotation (radians) of the last successful reading.
+    last: Option<(Instant, f64)>,
+}
+
+impl Default for SensorState {
+    fn default() -> Self {
+        Self {
+            health: ImuHealth::Healthy,
+            last: None,
+        }
+    }
+}
 
 /// Wrapper around one or more inertial sensors.
 ///
 /// This abstraction allows multiple IMUs to be used together to improve
-/// reliability by averaging their reported rotations.
+/// reliability by combining their reported rotations.
+///
+/// Sensors that disconnect, jump, or disagree with the others are excluded
+/// from the combined rotation, and changes in their health are logged. If
+/// every sensor fails, [`Imu::try_rotation`] returns `None` so the caller
+/// can fall back to another heading source, while [`Imu::rotation`] holds
+/// the last good rotation.
 pub struct Imu {
     /// Collection of inertial sensors used for heading estimation.
-    imus: Vec<InertialSensor>,
-    ratio: f64
+    imus: Vec<Box<dyn ImuDevice>>,
+
+    /// Scale and variance of each sensor, in the same order as `imus`.
+    calibrations: Vec<ImuCalibration>,
+
+    /// Fault tracking state of each sensor.
+    states: Vec<SensorState>,
+
+    /// Last combined rotation (radians) produced by a healthy sensor.
+    last_rotation: Option<f64>,
+
+    /// Whether too few sensors to outvote one another disagreed at the last
+    /// reading, to warn once.
+    unresolved: bool,
 }
 
 impl Imu {
+    /// Smallest variance used when weighting, so a perfect sensor
+    /// cannot produce an infinite weight.
+    const MIN_VARIANCE: f64 = 1e-9;
+
+    /// Fastest rotation the robot is able to make (radians/sec). A sensor
+    /// whose reading changes faster than this has jumped.
+    const MAX_ANGULAR_VELOCITY: f64 = 4.0 * TAU;
+
+    /// Allowed change between readings on top of `MAX_ANGULAR_VELOCITY`.
+    const JUMP_MARGIN: f64 = 0.05;
+
+    /// Largest difference (radians) allowed between a sensor and the median
+    /// before it is considered to disagree.
+    const MAX_DISAGREEMENT: f64 = 3.0_f64.to_radians();
+
+    /// Longest a single calibration attempt may take.
+    const CALIBRATION_TIMEOUT: Duration = Duration::from_secs(4);
+
+    /// Number of times a sensor is calibrated before giving up.
+    const CALIBRATION_ATTEMPTS: usize = 3;
+
     /// Creates a new IMU system from a list of inertial sensors.
-    pub fn new(imus: Vec<InertialSensor>, ratio: f64) -> Self {
-        Self { imus, ratio}
+    ///
+    /// `ratio` is used as the scale factor of every sensor until a
+    /// per-sensor calibration is set.
+    pub fn new<I: ImuDevice + 'static>(imus: Vec<I>, ratio: f64) -> Self {
+        let calibrations = vec![
+            ImuCalibration {
+                scale: ratio,
+                ..Default::default()
+            };
+            imus.len()
+        ];
+
+        let states = vec![SensorState::default(); imus.len()];
+
+        Self {
+            imus: imus
+                .into_iter()
+                .map(|imu| Box::new(imu) as Box<dyn ImuDevice>)
+                .collect(),
+            calibrations,
+            states,
+            last_rotation: None,
+            unresolved: false,
+        }
     }
 
-    /// Calibrates all inertial sensors.
+    /// Sets the scale and variance of each sensor.
     ///
-    /// Each sensor is calibrated sequentially. Errors are logged if a
-    /// calibration fails.
-    pub async fn calibrate(&mut self) {
-        for imu in self.imus.iter_mut() {
-            match imu.calibrate().await {
-                Ok(_) => info!("Calibration Successful"),
-                Err(e) => error!("Error {:?}", e),
+    /// Calibrations are matched to sensors by index. Sensors without a
+    /// calibration keep their current one.
+    pub fn set_calibration(&mut self, calibrations: Vec<ImuCalibration>) {
+        for (current, calibration) in self.calibrations.iter_mut().zip(calibrations) {
+            *current = calibration;
+        }
+
+        debug!("IMU calibration: {:?}", self.calibrations);
+    }
+
+    /// Calibrates all inertial sensors at the same time.
+    ///
+    /// Sensors that fail or do not finish within `CALIBRATION_TIMEOUT` are
+    /// retried together, up to `CALIBRATION_ATTEMPTS` times, so startup
+    /// takes one calibration period regardless of the number of sensors.
+    /// Sensors that never calibrate are excluded from the rotation.
+    ///
+    /// Returns the outcome for each sensor.
+    pub async fn calibrate(&mut self) -> Vec<CalibrationStatus> {
+        let mut statuses = vec![CalibrationStatus::TimedOut; self.imus.len()];
+
+        for attempt in 1..=Self::CALIBRATION_ATTEMPTS {
+            let mut pending: Vec<_> = self
+                .imus
+                .iter_mut()
+                .enumerate()
+                .filter(|(i, _)| !matches!(statuses[*i], CalibrationStatus::Calibrated(_)))
+                .map(|(i, imu)| (i, imu.calibrate()))
+                .collect();
+
+            if pending.is_empty() {
+                break;
+            }
+
+            let start = clock::now();
+
+            // Poll every calibration until they have all finished or timed out
+            while !pending.is_empty() && clock::elapsed(start) < Self::CALIBRATION_TIMEOUT {
+                poll_fn(|cx| {
+                    pending.retain_mut(|(i, calibration)| match calibration.as_mut().poll(cx) {
+                        Poll::Ready(true) => {
+                            statuses[*i] = CalibrationStatus::Calibrated(attempt);
+                            false
+                        }
+                        Poll::Ready(false) => {
+                            warn!("IMU {i}: calibration attempt {attempt} failed");
+                            statuses[*i] = CalibrationStatus::Failed;
+                            false
+                        }
+                        Poll::Pending => true,
+                    });
+
+                    Poll::Ready(())
+                })
+                .await;
+
+                sleep(Duration::from_millis(10)).await;
+            }
+
+            for (i, _) in pending {
+                warn!("IMU {i}: calibration attempt {attempt} timed out");
+                statuses[i] = CalibrationStatus::TimedOut;
             }
         }
+
+        self.reset_health();
+
+        for (i, status) in statuses.iter().enumerate() {
+            match status {
+                CalibrationStatus::Calibrated(attempts) => {
+                    info!("IMU {i}: calibrated in {attempts} attempt(s)");
+                    self.states[i] = SensorState::default();
+                }
+                _ => {
+                    error!("IMU {i}: calibration {:?}", status);
+                    self.states[i].health = ImuHealth::Uncalibrated;
+                }
+            }
+        }
+
+        statuses
     }
 
     /// Sets the heading reference for all inertial sensors.
@@ -39,39 +271,345 @@ impl Imu {
     /// This resets both the sensor rotation and heading to the provided value.
     pub fn set_rotation(&mut self, heading: Angle) {
         for imu in self.imus.iter_mut() {
-            _ = imu.set_rotation(heading);
-            _ = imu.set_heading(heading.wrapped_full())
+            imu.set_rotation(heading);
+            imu.set_heading(heading.wrapped_full());
         }
+
+        self.reset_health();
     }
 
-    /// Returns the averaged raw rotation from all sensors.
+    /// Returns the health of each sensor.
+    pub fn health(&self) -> Vec<ImuHealth> {
+        self.states.iter().map(|state| state.health).collect()
+    }
+
+    /// Returns `true` if at least one sensor is healthy.
+    pub fn is_healthy(&self) -> bool {
+        self.states
+            .iter()
+            .any(|state| state.health == ImuHealth::Healthy)
+    }
+
+    /// Returns the unscaled rotation of each sensor, or `None` for sensors
+    /// that could not be read.
+    pub fn raw_rotations(&self) -> Vec<Option<Angle>> {
+        self.imus
+            .iter()
+            .map(|imu| {
+                imu.rotation()
+                    .map(|rotation| Angle::from_radians(TAU - rotation.as_radians()))
+            })
+            .collect()
+    }
+
+    /// Returns the combined rotation from all healthy sensors, or `None`
+    /// if every sensor has failed.
     ///
-    /// Rotations are converted to radians and averaged to reduce noise
-    /// when multiple sensors are present.
-    pub fn rotation(&self) -> Angle {
-        let mut angles = Vec::new();
-        for imu in self.imus.iter() {
-            if let Ok(rotation) = imu.rotation() {
-                angles.push(TAU - rotation.as_radians());
+    /// Each sensor's rotation is corrected by its scale factor and checked
+    /// for faults:
+    ///
+    /// - a failed read marks the sensor disconnected, and reading it again
+    ///   afterwards marks it reconnected
+    /// - a change faster than the robot can turn marks it jumped
+    /// - with at least three remaining sensors, a difference from their
+    ///   median larger than `MAX_DISAGREEMENT` marks it disagreeing
+    ///
+    /// With fewer than three, a disagreement cannot be pinned on one sensor,
+    /// so a warning is logged and none are excluded. The remaining sensors
+    /// are averaged, weighted by the inverse of their variance so that the
+    /// more stable sensor dominates.
+    pub fn try_rotation(&mut self) -> Option<Angle> {
+        let now = clock::now();
+        let mut readings = Vec::new();
+
+        for (i, rotation) in self.raw_rotations().into_iter().enumerate() {
+            if self.states[i].health == ImuHealth::Uncalibrated {
+                continue;
+            }
+
+            let Some(rotation) = rotation else {
+                // The last reading says nothing about where a sensor that
+                // comes back will be
+                self.states[i].last = None;
+                self.set_health(i, ImuHealth::Disconnected);
+                continue;
+            };
+
+            if self.states[i].health == ImuHealth::Disconnected {
+                self.set_health(i, ImuHealth::Reconnected);
+            }
+
+            let calibration = self.calibrations[i];
+            let rotation = calibration.scale * rotation.as_radians();
+            let previous = self.states[i].last.replace((now, rotation));
+
+            if self.states[i].health.is_latched() {
+                continue;
             }
+
+            if let Some((time, previous)) = previous {
+                let dt = now.duration_since(time).as_secs_f64();
+                if (rotation - previous).abs() > Self::MAX_ANGULAR_VELOCITY * dt + Self::JUMP_MARGIN
+                {
+                    self.set_health(i, ImuHealth::Jumped);
+                    continue;
+                }
+            }
+
+            let weight = 1.0 / calibration.variance.max(Self::MIN_VARIANCE);
+            readings.push((i, rotation, weight));
+        }
+
+        // With three or more sensors a faulty one can be told apart from the
+        // rest by comparing against the median. With fewer there is no
+        // majority to side with, so the sensors are fused as they are.
+        let median = if readings.len() >= 3 {
+            let mut sorted: Vec<f64> = readings.iter().map(|(_, rotation, _)| *rotation).collect();
+            sorted.sort_by(f64::total_cmp);
+            Some(sorted[sorted.len() / 2])
+        } else {
+            let (min, max) = readings
+                .iter()
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), reading| {
+                    (min.min(reading.1), max.max(reading.1))
+                });
+            let spread = (max - min).max(0.0);
+            let disagreeing = spread > Self::MAX_DISAGREEMENT;
+            if disagreeing && !self.unresolved {
+                warn!(
+                    "IMUs disagree by {:.1} deg with too few to outvote, using weighted mean",
+                    spread.to_degrees()
+                );
+            }
+            self.unresolved = disagreeing;
+            None
+        };
+
+        let mut sum = 0.0;
+        let mut total_weight = 0.0;
+
+        for (i, rotation, weight) in readings {
+            if median.is_some_and(|median| (rotation - median).abs() > Self::MAX_DISAGREEMENT) {
+                self.set_health(i, ImuHealth::Disagreeing);
+                continue;
+            }
+
+            self.set_health(i, ImuHealth::Healthy);
+            sum += weight * rotation;
+            total_weight += weight;
+        }
+
+        if total_weight == 0.0 {
+            return None;
         }
 
-        Angle::from_radians(average(&angles)*self.ratio)
+        let rotation = sum / total_weight;
+        self.last_rotation = Some(rotation);
+
+        Some(Angle::from_radians(rotation))
+    }
+
+    /// Returns the combined rotation from all healthy sensors.
+    ///
+    /// If every sensor has failed, the last good rotation is held so the
+    /// heading stops changing instead of becoming NaN.
+    pub fn rotation(&mut self) -> Angle {
+        let rotation = self.try_rotation();
+
+        rotation.unwrap_or(Angle::from_radians(self.last_rotation.unwrap_or_default()))
     }
 
     /// Returns the robot heading normalized to `[0, 2π)`.
-    pub fn heading(&self) -> Angle {
+    pub fn heading(&mut self) -> Angle {
         self.rotation().wrapped_full()
     }
 
     pub fn pitch(&self) -> Vec<Angle> {
         let mut pitch = vec![];
         for imu in self.imus.iter() {
-            if let Ok(euler) = imu.euler() {
-                pitch.push(euler.a);
+            if let Some(imu_pitch) = imu.pitch() {
+                pitch.push(imu_pitch);
             }
         }
 
         pitch
     }
+
+    /// Updates the health of a sensor, logging any change.
+    fn set_health(&mut self, index: usize, health: ImuHealth) {
+        let previous = self.states[index].health;
+        if previous == health {
+            return;
+        }
+
+        self.states[index].health = health;
+
+        match health {
+            ImuHealth::Healthy => info!("IMU {index}: {:?} -> Healthy", previous),
+            _ => warn!("IMU {index}: {:?} -> {:?}", previous, health),
+        }
+
+        if !self.is_healthy() {
+            error!("All IMUs have failed, holding last rotation");
+        }
+    }
+
+    /// Marks every calibrated sensor healthy and forgets their previous
+    /// readings.
+    fn reset_health(&mut self) {
+        for state in self.states.iter_mut() {
+            if state.health != ImuHealth::Uncalibrated {
+                *state = SensorState::default();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vexide::runtime::block_on;
+
+    use super::*;
+    use crate::hardware::mock::MockImu;
+
+    fn degrees(angle: Option<Angle>) -> Option<f64> {
+        angle.map(|angle| angle.as_degrees())
+    }
+
+    #[test]
+    fn combines_healthy_sensors() {
+        let sensors = [MockImu::new(), MockImu::new()];
+        sensors[0].set_robot_rotation(Angle::from_degrees(10.0));
+        sensors[1].set_robot_rotation(Angle::from_degrees(11.0));
+
+        let mut imu = Imu::new(sensors.to_vec(), 1.0);
+        let rotation = degrees(imu.try_rotation()).unwrap();
+
+        assert!((rotation - 10.5).abs() < 1e-6);
+        assert_eq!(imu.health(), vec![ImuHealth::Healthy; 2]);
+    }
+
+    #[test]
+    fn excludes_disconnected_sensor() {
+        let sensors = [MockImu::new(), MockImu::new()];
+        sensors[0].set_robot_rotation(Angle::from_degrees(10.0));
+        sensors[1].set_robot_rotation(Angle::from_degrees(10.0));
+
+        let mut imu = Imu::new(sensors.to_vec(), 1.0);
+        sensors[1].update(|state| state.connected = false);
+        let rotation = degrees(imu.try_rotation()).unwrap();
+
+        assert!((rotation - 10.0).abs() < 1e-6);
+        assert_eq!(
+            imu.health(),
+            vec![ImuHealth::Healthy, ImuHealth::Disconnected]
+        );
+    }
+
+    #[test]
+    fn reconnected_sensor_is_excluded_until_reset() {
+        let sensors = [MockImu::new(), MockImu::new()];
+        for sensor in &sensors {
+            sensor.set_robot_rotation(Angle::from_degrees(90.0));
+        }
+
+        let mut imu = Imu::new(sensors.to_vec(), 1.0);
+        imu.try_rotation();
+
+        // The second sensor power cycles and comes back reading zero
+        sensors[1].update(|state| state.connected = false);
+        imu.try_rotation();
+        sensors[1].update(|state| state.connected = true);
+        sensors[1].set_robot_rotation(Angle::ZERO);
+        let rotation = degrees(imu.try_rotation()).unwrap();
+
+        assert!((rotation - 90.0).abs() < 1e-6);
+        assert_eq!(
+            imu.health(),
+            vec![ImuHealth::Healthy, ImuHealth::Reconnected]
+        );
+
+        imu.set_rotation(Angle::from_degrees(90.0));
+        for sensor in &sensors {
+            sensor.set_robot_rotation(Angle::from_degrees(90.0));
+        }
+        let rotation = degrees(imu.try_rotation()).unwrap();
+
+        assert!((rotation - 90.0).abs() < 1e-6);
+        assert_eq!(imu.health(), vec![ImuHealth::Healthy; 2]);
+    }
+
+    #[test]
+    fn holds_last_rotation_when_all_fail() {
+        let sensor = MockImu::new();
+        sensor.set_robot_rotation(Angle::from_degrees(45.0));
+
+        let mut imu = Imu::new(vec![sensor.clone()], 1.0);
+        imu.try_rotation();
+        sensor.update(|state| state.connected = false);
+
+        assert!(imu.try_rotation().is_none());
+        assert!((imu.rotation().as_degrees() - 45.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn recalibrating_restores_failed_sensor() {
+        let sensor = MockImu::new();
+        sensor.update(|state| state.calibrates = false);
+
+        let mut imu = Imu::new(vec![sensor.clone()], 1.0);
+        block_on(imu.calibrate());
+        assert_eq!(imu.health(), vec![ImuHealth::Uncalibrated]);
+
+        sensor.update(|state| state.calibrates = true);
+        let statuses = block_on(imu.calibrate());
+
+        assert_eq!(statuses, vec![CalibrationStatus::Calibrated(1)]);
+        assert_eq!(imu.health(), vec![ImuHealth::Healthy]);
+    }
+
+    #[test]
+    fn two_disagreeing_sensors_use_weighted_mean() {
+        let sensors = [MockImu::new(), MockImu::new()];
+        sensors[0].set_robot_rotation(Angle::from_degrees(10.0));
+        sensors[1].set_robot_rotation(Angle::from_degrees(20.0));
+
+        let mut imu = Imu::new(sensors.to_vec(), 1.0);
+        imu.set_calibration(vec![
+            ImuCalibration {
+                variance: 1.0,
+                ..Default::default()
+            },
+            ImuCalibration {
+                variance: 3.0,
+                ..Default::default()
+            },
+        ]);
+        let rotation = degrees(imu.try_rotation()).unwrap();
+
+        // Neither sensor can be outvoted, so both are kept
+        assert!((rotation - 12.5).abs() < 1e-6);
+        assert_eq!(imu.health(), vec![ImuHealth::Healthy; 2]);
+    }
+
+    #[test]
+    fn median_excludes_disagreeing_sensor() {
+        let sensors = [MockImu::new(), MockImu::new(), MockImu::new()];
+        sensors[0].set_robot_rotation(Angle::from_degrees(10.0));
+        sensors[1].set_robot_rotation(Angle::from_degrees(11.0));
+        sensors[2].set_robot_rotation(Angle::from_degrees(30.0));
+
+        let mut imu = Imu::new(sensors.to_vec(), 1.0);
+        let rotation = degrees(imu.try_rotation()).unwrap();
+
+        assert!((rotation - 10.5).abs() < 1e-6);
+        assert_eq!(
+            imu.health(),
+            vec![
+                ImuHealth::Healthy,
+                ImuHealth::Healthy,
+                ImuHealth::Disagreeing
+            ]
+        );
+    }
 }
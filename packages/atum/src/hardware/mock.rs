@@ -0,0 +1,389 @@
+//! Mock Devices
+//!
+//! In-memory implementations of the device traits for running the library
+//! without a robot, e.g. in `cargo test` or a simulation.
+//!
+//! Each mock is a cheap handle to shared state: keep a clone after handing
+//! one to a hardware wrapper to set what it reads and inspect what it was
+//! commanded.
+
+use std::{
+    cell::{Cell, RefCell},
+    f64::consts::TAU,
+    rc::Rc,
+};
+
+use vexide::{
+    math::Angle,
+    smart::{distance::DistanceObjectError, motor::BrakeMode},
+};
+
+use super::{
+    devices::{
+        BatteryDevice, CalibrationFuture, DigitalOutDevice, DistanceDevice, DistanceReading,
+        ImuDevice, MotorDevice, MotorFaults,
+    },
+    encoder::Encoder,
+};
+
+/// State of a [`MockMotor`].
+#[derive(Debug, Clone, Copy)]
+pub struct MockMotorState {
+    /// Last commanded voltage.
+    pub voltage: f64,
+
+    /// Last commanded velocity (RPM), if velocity control was used.
+    pub target_velocity: Option<i32>,
+
+    /// Last commanded position target and velocity (RPM), if position
+    /// control was used.
+    pub target_position: Option<(Angle, i32)>,
+
+    /// Last commanded brake mode, if the motor was stopped.
+    pub brake: Option<BrakeMode>,
+
+    /// Velocity reported by the motor (RPM).
+    pub velocity: f64,
+
+    /// Rotation reported by the motor's encoder.
+    pub position: Angle,
+
+    /// Temperature reported by the motor (°C).
+    pub temperature: f64,
+
+    /// Current reported by the motor (A).
+    pub current: f64,
+
+    /// Efficiency reported by the motor (percent).
+    pub efficiency: f64,
+
+    /// Faults reported by the motor.
+    pub faults: MotorFaults,
+
+    /// Last commanded current limit (A).
+    pub current_limit: f64,
+
+    /// Whether the motor responds. Reads return `None` when disconnected.
+    pub connected: bool,
+}
+
+impl Default for MockMotorState {
+    fn default() -> Self {
+        Self {
+            voltage: 0.0,
+            target_velocity: None,
+            target_position: None,
+            brake: None,
+            velocity: 0.0,
+            position: Angle::ZERO,
+            temperature: 25.0,
+            current: 0.0,
+            efficiency: 100.0,
+            faults: MotorFaults::default(),
+            current_limit: 2.5,
+            connected: true,
+        }
+    }
+}
+
+/// A motor whose readings are set by hand.
+#[derive(Clone, Default)]
+pub struct MockMotor {
+    state: Rc<RefCell<MockMotorState>>,
+}
+
+impl MockMotor {
+    /// Creates a connected motor at rest.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a copy of the motor's state.
+    pub fn state(&self) -> MockMotorState {
+        *self.state.borrow()
+    }
+
+    /// Modifies the motor's state.
+    pub fn update(&self, f: impl FnOnce(&mut MockMotorState)) {
+        f(&mut self.state.borrow_mut());
+    }
+}
+
+impl MotorDevice for MockMotor {
+    fn set_voltage(&mut self, voltage: f64) {
+        self.update(|state| {
+            state.voltage = voltage;
+            state.target_velocity = None;
+            state.target_position = None;
+            state.brake = None;
+        });
+    }
+
+    fn set_velocity(&mut self, rpm: i32) {
+        self.update(|state| {
+            state.target_velocity = Some(rpm);
+            state.target_position = None;
+            state.brake = None;
+        });
+    }
+
+    fn set_position_target(&mut self, position: Angle, rpm: i32) {
+        self.update(|state| {
+            state.voltage = 0.0;
+            state.target_velocity = None;
+            state.target_position = Some((position, rpm));
+            state.brake = None;
+        });
+    }
+
+    fn brake(&mut self, mode: BrakeMode) {
+        self.update(|state| {
+            state.voltage = 0.0;
+            state.target_velocity = None;
+            state.target_position = None;
+            state.brake = Some(mode);
+        });
+    }
+
+    fn set_current_limit(&mut self, limit: f64) {
+        self.update(|state| state.current_limit = limit);
+    }
+
+    fn voltage(&self) -> Option<f64> {
+        let state = self.state();
+        state.connected.then_some(state.voltage)
+    }
+
+    fn velocity(&self) -> Option<f64> {
+        let state = self.state();
+        state.connected.then_some(state.velocity)
+    }
+
+    fn position(&self) -> Option<Angle> {
+        let state = self.state();
+        state.connected.then_some(state.position)
+    }
+
+    fn temperature(&self) -> Option<f64> {
+        let state = self.state();
+        state.connected.then_some(state.temperature)
+    }
+
+    fn current(&self) -> Option<f64> {
+        let state = self.state();
+        state.connected.then_some(state.current)
+    }
+
+    fn efficiency(&self) -> Option<f64> {
+        let state = self.state();
+        state.connected.then_some(state.efficiency)
+    }
+
+    fn faults(&self) -> Option<MotorFaults> {
+        let state = self.state();
+        state.connected.then_some(state.faults)
+    }
+}
+
+/// State of a [`MockImu`].
+#[derive(Debug, Clone, Copy)]
+pub struct MockImuState {
+    /// Rotation reported by the sensor (clockwise positive).
+    pub rotation: Angle,
+
+    /// Pitch reported by the sensor.
+    pub pitch: Angle,
+
+    /// Whether calibration succeeds.
+    pub calibrates: bool,
+
+    /// Whether the sensor responds. Reads return `None` when disconnected.
+    pub connected: bool,
+}
+
+impl Default for MockImuState {
+    fn default() -> Self {
+        Self {
+            rotation: Angle::ZERO,
+            pitch: Angle::ZERO,
+            calibrates: true,
+            connected: true,
+        }
+    }
+}
+
+/// An inertial sensor whose readings are set by hand.
+#[derive(Clone, Default)]
+pub struct MockImu {
+    state: Rc<RefCell<MockImuState>>,
+}
+
+impl MockImu {
+    /// Creates a connected sensor at zero rotation.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a copy of the sensor's state.
+    pub fn state(&self) -> MockImuState {
+        *self.state.borrow()
+    }
+
+    /// Modifies the sensor's state.
+    pub fn update(&self, f: impl FnOnce(&mut MockImuState)) {
+        f(&mut self.state.borrow_mut());
+    }
+
+    /// Sets the sensor's rotation from a robot heading (counterclockwise
+    /// positive), matching the conversion made by [`Imu`](super::imu::Imu).
+    pub fn set_robot_rotation(&self, rotation: Angle) {
+        self.update(|state| state.rotation = Angle::from_radians(TAU - rotation.as_radians()));
+    }
+}
+
+impl ImuDevice for MockImu {
+    fn calibrate(&mut self) -> CalibrationFuture<'_> {
+        let calibrates = self.state().calibrates;
+        Box::pin(async move { calibrates })
+    }
+
+    fn rotation(&self) -> Option<Angle> {
+        let state = self.state();
+        state.connected.then_some(state.rotation)
+    }
+
+    fn pitch(&self) -> Option<Angle> {
+        let state = self.state();
+        state.connected.then_some(state.pitch)
+    }
+
+    fn set_rotation(&mut self, rotation: Angle) {
+        self.update(|state| state.rotation = rotation);
+    }
+
+    fn set_heading(&mut self, _heading: Angle) {}
+}
+
+/// An encoder whose position is set by hand.
+///
+/// `None` simulates a failed read.
+#[derive(Clone)]
+pub struct MockEncoder {
+    position: Rc<RefCell<Option<Angle>>>,
+}
+
+impl Default for MockEncoder {
+    fn default() -> Self {
+        Self {
+            position: Rc::new(RefCell::new(Some(Angle::ZERO))),
+        }
+    }
+}
+
+impl MockEncoder {
+    /// Creates an encoder at zero.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the position the encoder reports.
+    pub fn set_position(&self, position: Option<Angle>) {
+        self.position.replace(position);
+    }
+}
+
+impl Encoder for MockEncoder {
+    fn position(&self) -> Option<Angle> {
+        *self.position.borrow()
+    }
+}
+
+/// A distance sensor whose reading is set by hand.
+#[derive(Clone, Default)]
+pub struct MockDistanceSensor {
+    reading: Rc<RefCell<Option<DistanceReading>>>,
+}
+
+impl MockDistanceSensor {
+    /// Creates a sensor that detects nothing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the object the sensor detects, if any.
+    pub fn set_reading(&self, reading: Option<DistanceReading>) {
+        self.reading.replace(reading);
+    }
+
+    /// Detects a wall-sized object `inches` away.
+    pub fn set_distance(&self, inches: f64) {
+        self.set_reading(Some(DistanceReading {
+            distance: (inches * 25.4).round() as u32,
+            relative_size: Some(100),
+        }));
+    }
+}
+
+impl DistanceDevice for MockDistanceSensor {
+    fn object(&self) -> Result<Option<DistanceReading>, DistanceObjectError> {
+        Ok(*self.reading.borrow())
+    }
+}
+
+/// A battery whose voltage is set by hand.
+///
+/// `None` simulates a failed read.
+#[derive(Clone)]
+pub struct MockBattery {
+    voltage: Rc<Cell<Option<f64>>>,
+}
+
+impl Default for MockBattery {
+    fn default() -> Self {
+        Self {
+            voltage: Rc::new(Cell::new(Some(12.8))),
+        }
+    }
+}
+
+impl MockBattery {
+    /// Creates a battery at its nominal voltage.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the voltage the battery reports.
+    pub fn set_voltage(&self, voltage: Option<f64>) {
+        self.voltage.set(voltage);
+    }
+}
+
+impl BatteryDevice for MockBattery {
+    fn voltage(&self) -> Option<f64> {
+        self.voltage.get()
+    }
+}
+
+/// A digital output that records its level.
+#[derive(Clone, Default)]
+pub struct MockDigitalOut {
+    high: Rc<Cell<bool>>,
+}
+
+impl MockDigitalOut {
+    /// Creates a low output.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` if the output was last driven high.
+    pub fn is_high(&self) -> bool {
+        self.high.get()
+    }
+}
+
+impl DigitalOutDevice for MockDigitalOut {
+    fn set_level(&mut self, high: bool) {
+        self.high.set(high);
+    }
+}
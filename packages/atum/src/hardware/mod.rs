@@ -5,16 +5,26 @@
 //! allowing the rest of the code to work with unified interfaces.
 //!
 //! Submodules:
+//! - [`battery`] – Battery voltage compensation and low battery warnings.
+//! - [`devices`] – Traits over the vexide devices used by the wrappers below.
+//! - [`encoder`] – Common interface over ADI encoders, rotation sensors and motors.
 //! - [`imu`] – Inertial Measurement Unit wrapper for heading and rotation.
+//! - [`mock`] – In-memory devices for running without a robot.
 //! - [`motor_group`] – Groups of motors with convenient control methods.
+//! - [`pneumatic`] – Pneumatic cylinders with named states and air tracking.
 //! - [`tracking_wheel`] – Passive tracking wheels used for odometry.
 //! - [`wall_distance_sensor`] – Distance Sensor wrapper for localization
 //! 
 //!
 //! # Utilities
 //! - `average` – Computes the arithmetic mean of a list of f64 values
+pub mod battery;
+pub mod devices;
+pub mod encoder;
 pub mod imu;
+pub mod mock;
 pub mod motor_group;
+pub mod pneumatic;
 pub mod tracking_wheel;
 pub mod wall_distance_sensor;
 
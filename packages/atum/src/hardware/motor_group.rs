@@ -6,56 +6,512 @@
 //!
 //! Submodules / structs:
 //! - `MotorGroup` – Represents a group of motors that should move together.
+//! - `MotorHealth` – Temperature, current and faults of a single motor.
+//! - `DeratingParameters` – How current limits are lowered as motors heat up.
+//! - `PositionController` – How a group holds a position target.
 //! - `MotorController` – PID + feedforward controller for a motor.
 
-use std::time::Instant;
-use vexide::{prelude::Motor, smart::motor::BrakeMode};
-use super::average;
-use crate::controllers::pid::Pid;
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+use log::{info, warn};
+use vexide::{math::Angle, prelude::Motor, smart::motor::BrakeMode};
+use super::{
+    average,
+    battery::Battery,
+    devices::{MotorDevice, MotorFaults},
+};
+use crate::{clock, controllers::pid::Pid};
+
+/// Temperature (°C) at which V5 motors start limiting their output.
+pub const HOT_TEMPERATURE: f64 = 55.0;
+
+/// Health of a single motor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MotorHealth {
+    /// Whether the motor responds. The readings are `None` when it does not.
+    pub connected: bool,
+
+    /// Temperature of the motor (°C).
+    pub temperature: Option<f64>,
+
+    /// Current drawn by the motor (A).
+    pub current: Option<f64>,
+
+    /// Efficiency of the motor (percent).
+    pub efficiency: Option<f64>,
+
+    /// Faults reported by the motor.
+    pub faults: MotorFaults,
+}
+
+impl MotorHealth {
+    fn read(motor: &dyn MotorDevice) -> Self {
+        let temperature = motor.temperature();
+
+        Self {
+            connected: temperature.is_some(),
+            temperature,
+            current: motor.current(),
+            efficiency: motor.efficiency(),
+            faults: motor.faults().unwrap_or_default(),
+        }
+    }
+
+    /// Returns `true` if the motor is at or above [`HOT_TEMPERATURE`].
+    pub fn is_hot(&self) -> bool {
+        self.temperature
+            .is_some_and(|temperature| temperature >= HOT_TEMPERATURE)
+    }
+
+    /// Returns `true` if the motor is connected, not hot and has no faults.
+    pub fn is_healthy(&self) -> bool {
+        self.connected && !self.is_hot() && !self.faults.any()
+    }
+}
+
+impl Default for MotorHealth {
+    fn default() -> Self {
+        Self {
+            connected: true,
+            temperature: None,
+            current: None,
+            efficiency: None,
+            faults: MotorFaults::default(),
+        }
+    }
+}
+
+/// Time between temperature reads when derating.
+const DERATING_PERIOD: Duration = Duration::from_millis(250);
+
+/// How a motor group lowers its current limits as its motors heat up.
+///
+/// Between `start_temperature` and `max_temperature` each motor's current
+/// limit is reduced linearly, so the motor cools down gradually instead of
+/// hitting the firmware's hard limit at 55°C.
+///
+/// The motors of a group are geared together and always turn at the same
+/// speed, so they all keep the same voltage. Scaling a hot motor's voltage
+/// down would leave it below its own back-EMF, braking against the others
+/// and heating up further. Lowering its current limit instead lets the cool
+/// motors take up the load it sheds.
+#[derive(Debug, Clone, Copy)]
+pub struct DeratingParameters {
+    /// Temperature at which derating starts (°C).
+    pub start_temperature: f64,
+
+    /// Temperature at which the current limit reaches `min_current_limit` (°C).
+    pub max_temperature: f64,
+
+    /// Current limit of a cool motor (A).
+    pub current_limit: f64,
+
+    /// Current limit of a motor at `max_temperature` (A).
+    pub min_current_limit: f64,
+}
+
+impl Default for DeratingParameters {
+    fn default() -> Self {
+        Self {
+            start_temperature: 45.0,
+            max_temperature: HOT_TEMPERATURE,
+            current_limit: 2.5,
+            min_current_limit: 1.25,
+        }
+    }
+}
+
+impl DeratingParameters {
+    /// Returns how far into the derating range `temperature` is, from `0.0`
+    /// (cool) to `1.0` (at or above `max_temperature`).
+    fn heat(&self, temperature: Option<f64>) -> f64 {
+        let Some(temperature) = temperature else {
+            return 0.0;
+        };
+
+        let range = self.max_temperature - self.start_temperature;
+        if range <= 0.0 {
+            return if temperature >= self.max_temperature {
+                1.0
+            } else {
+                0.0
+            };
+        }
+
+        ((temperature - self.start_temperature) / range).clamp(0.0, 1.0)
+    }
+
+    /// Returns the current limit of a motor at `temperature`.
+    pub fn current_limit(&self, temperature: Option<f64>) -> f64 {
+        let heat = self.heat(temperature);
+        self.current_limit + (self.min_current_limit - self.current_limit) * heat
+    }
+}
+
+/// Time assumed between position updates before the first one.
+const POSITION_PERIOD: Duration = Duration::from_millis(10);
+
+/// How a motor group drives to and holds a position target.
+#[derive(Clone, Copy)]
+pub enum PositionController {
+    /// The motors' built-in position controllers, moving at up to `rpm`.
+    ///
+    /// Targets are sent once and held by the motors themselves.
+    BuiltIn { rpm: i32 },
+
+    /// A PID loop on the group's output position, with the error in degrees
+    /// and the output in volts.
+    ///
+    /// [`MotorGroup::update_position`] must be called periodically (e.g.
+    /// every 10ms) to run the loop.
+    Pid(Pid),
+}
+
+impl Default for PositionController {
+    fn default() -> Self {
+        Self::BuiltIn { rpm: 200 }
+    }
+}
 
 /// A group of motors that can be controlled together
 pub struct MotorGroup {
-    motors: Vec<Motor>,
+    motors: Vec<Box<dyn MotorDevice>>,
+
+    /// Handles to the same motors, shared with encoders reading them.
+    shared: Vec<Rc<RefCell<dyn MotorDevice>>>,
+
     motor_controller: Option<MotorController>,
+    name: &'static str,
+
+    /// Health of each motor at the last check, to log changes once.
+    health: Vec<MotorHealth>,
+
+    /// Thermal derating, if enabled.
+    derating: Option<DeratingParameters>,
+
+    /// Whether any motor was derated at the last update.
+    derated: bool,
+
+    /// Current limit last sent to each motor.
+    current_limits: Vec<Option<f64>>,
+
+    /// When the current limits were last updated.
+    derating_updated: Option<Instant>,
+
+    /// Battery used to compensate voltage commands, if enabled.
+    battery: Option<Battery>,
+
+    /// Encoder position of each motor at the last tare.
+    zeros: Vec<Angle>,
+
+    /// Output rotation per motor rotation.
+    gear_ratio: f64,
+
+    /// Controller used for position targets.
+    position_controller: PositionController,
+
+    /// Position target of the output, if position control is active.
+    position_target: Option<Angle>,
+
+    /// When the position loop last ran.
+    position_updated: Option<Instant>,
 }
 
 impl MotorGroup {
     /// Create a new motor group with optional motor controller for closed-loop control
-    pub fn new(motors: Vec<Motor>, motor_controller: Option<MotorController>) -> Self {
+    pub fn new<M: MotorDevice + 'static>(
+        motors: Vec<M>,
+        motor_controller: Option<MotorController>,
+    ) -> Self {
+        let health = vec![MotorHealth::default(); motors.len()];
+        let current_limits = vec![None; motors.len()];
+        let zeros = vec![Angle::ZERO; motors.len()];
+        let shared: Vec<Rc<RefCell<dyn MotorDevice>>> = motors
+            .into_iter()
+            .map(|motor| Rc::new(RefCell::new(motor)) as Rc<RefCell<dyn MotorDevice>>)
+            .collect();
+
         Self {
-            motors,
+            motors: shared
+                .iter()
+                .map(|motor| Box::new(motor.clone()) as Box<dyn MotorDevice>)
+                .collect(),
+            shared,
             motor_controller,
+            name: "motor group",
+            health,
+            derating: None,
+            derated: false,
+            current_limits,
+            derating_updated: None,
+            battery: None,
+            zeros,
+            gear_ratio: 1.0,
+            position_controller: PositionController::default(),
+            position_target: None,
+            position_updated: None,
+        }
+    }
+
+    /// Sets the name used for the group in health warnings, e.g. `"left drive"`.
+    pub fn set_name(&mut self, name: &'static str) -> &mut Self {
+        self.name = name;
+        self
+    }
+
+    /// Enables or disables thermal derating.
+    ///
+    /// Disabling it restores the default current limit.
+    pub fn set_derating(&mut self, derating: Option<DeratingParameters>) -> &mut Self {
+        if derating.is_none() {
+            let current_limit = DeratingParameters::default().current_limit;
+            for (motor, limit) in self.motors.iter_mut().zip(&mut self.current_limits) {
+                if limit.is_some() {
+                    motor.set_current_limit(current_limit);
+                    *limit = None;
+                }
+            }
+            self.derated = false;
+        }
+
+        self.derating = derating;
+        self.derating_updated = None;
+        self
+    }
+
+    /// Enables or disables battery voltage compensation.
+    ///
+    /// With compensation enabled, voltage commands are scaled by the
+    /// battery's nominal over actual voltage, so the same command produces
+    /// the same output on a fresh and a drained battery.
+    pub fn set_battery_compensation(&mut self, battery: Option<Battery>) -> &mut Self {
+        self.battery = battery;
+        self
+    }
+
+    /// Returns the scale applied to voltage commands by battery
+    /// compensation, `1.0` when it is disabled.
+    pub fn battery_compensation(&self) -> f64 {
+        self.battery
+            .as_ref()
+            .map_or(1.0, |battery| battery.compensation())
+    }
+
+    /// Sets the output rotation per motor rotation, e.g. `12.0 / 36.0` for a
+    /// 12 tooth gear on the motors driving a 36 tooth gear.
+    ///
+    /// Output positions and position targets are measured after this ratio.
+    pub fn set_gear_ratio(&mut self, ratio: f64) -> &mut Self {
+        self.gear_ratio = ratio;
+        self
+    }
+
+    /// Returns the output rotation per motor rotation.
+    pub fn gear_ratio(&self) -> f64 {
+        self.gear_ratio
+    }
+
+    /// Sets the controller used for position targets.
+    ///
+    /// Defaults to the built-in controllers at 200 RPM.
+    pub fn set_position_controller(&mut self, controller: PositionController) -> &mut Self {
+        self.position_controller = controller;
+        self.position_updated = None;
+        if let Some(target) = self.position_target {
+            self.set_position_target(target);
+        }
+        self
+    }
+
+    /// Re-reads the motor temperatures and updates the derating, at most
+    /// once per [`DERATING_PERIOD`].
+    fn update_derating(&mut self) {
+        let Some(derating) = self.derating else {
+            return;
+        };
+
+        let now = clock::now();
+        if self
+            .derating_updated
+            .is_some_and(|updated| now.duration_since(updated) < DERATING_PERIOD)
+        {
+            return;
+        }
+        self.derating_updated = Some(now);
+
+        let temperatures: Vec<Option<f64>> =
+            self.motors.iter().map(|motor| motor.temperature()).collect();
+        let derated = temperatures
+            .iter()
+            .any(|&temperature| temperature.is_some_and(|t| t > derating.start_temperature));
+        if derated != self.derated {
+            if derated {
+                let hottest = temperatures.iter().flatten().fold(f64::MIN, |a, &b| a.max(b));
+                warn!("{} derating, hottest motor at {hottest:.0}°C", self.name);
+            } else {
+                info!("{} cooled down, derating stopped", self.name);
+            }
+        }
+        self.derated = derated;
+
+        // Only send limits that changed noticeably
+        for ((motor, temperature), sent) in self
+            .motors
+            .iter_mut()
+            .zip(&temperatures)
+            .zip(&mut self.current_limits)
+        {
+            let limit = derating.current_limit(*temperature);
+            if sent.is_none_or(|sent| (sent - limit).abs() > 0.05) {
+                motor.set_current_limit(limit);
+                *sent = Some(limit);
+            }
         }
     }
 
     /// Apply the same voltage to all motors in the group
+    ///
+    /// With battery compensation enabled, the voltage is scaled by
+    /// [`battery_compensation`](Self::battery_compensation).
+    ///
+    /// Cancels any position target.
     pub fn set_voltage(&mut self, voltage: f64) {
+        self.position_target = None;
+        self.apply_voltage(voltage);
+    }
+
+    fn apply_voltage(&mut self, voltage: f64) {
+        self.update_derating();
+        let voltage = voltage * self.battery_compensation();
+
         for motor in self.motors.iter_mut() {
-            _ = motor.set_voltage(voltage);
+            motor.set_voltage(voltage);
         }
     }
 
     /// Apply a velocity setpoint to all motors, using VEX's default velocity controller
     /// if no velocity controller is provided
+    ///
+    /// Cancels any position target.
     pub fn set_velocity(&mut self, velocity: f64) {
+        self.position_target = None;
+        self.update_derating();
+
         for motor in self.motors.iter_mut() {
             match self.motor_controller {
                 Some(mut controller) => {
                     let motor_velocity = motor.velocity().unwrap_or_default();
                     let voltage = controller.output(velocity, motor_velocity, 0.0);
-                    _ = motor.set_voltage(voltage);
+                    motor.set_voltage(voltage);
                 }
                 None => {
-                    _ = motor.set_velocity(velocity as i32);
+                    motor.set_velocity(velocity as i32);
                 }
             }
         }
     }
 
+    /// Moves the output to `target`, measured from the last tare, and holds
+    /// it there.
+    ///
+    /// With [`PositionController::Pid`], [`update_position`](Self::update_position)
+    /// must be called periodically to drive the motors.
+    pub fn set_position_target(&mut self, target: Angle) {
+        if self.position_target.is_none() {
+            if let PositionController::Pid(pid) = &mut self.position_controller {
+                pid.reset();
+            }
+            self.position_updated = None;
+        }
+        self.position_target = Some(target);
+
+        if let PositionController::BuiltIn { rpm } = self.position_controller {
+            let motor_target = target.as_radians() / self.gear_ratio;
+            for (motor, zero) in self.motors.iter_mut().zip(&self.zeros) {
+                motor.set_position_target(*zero + Angle::from_radians(motor_target), rpm);
+            }
+        } else {
+            self.update_position();
+        }
+    }
+
+    /// Moves the output by `distance` from its current target, or from its
+    /// current position if it has none.
+    pub fn move_relative(&mut self, distance: Angle) {
+        let start = self
+            .position_target
+            .unwrap_or_else(|| self.output_position());
+        self.set_position_target(start + distance);
+    }
+
+    /// Holds the output at its current position.
+    pub fn hold(&mut self) {
+        self.set_position_target(self.output_position());
+    }
+
+    /// Returns the position target of the output, if position control is
+    /// active.
+    pub fn position_target(&self) -> Option<Angle> {
+        self.position_target
+    }
+
+    /// Returns the distance left to the position target, if position
+    /// control is active.
+    pub fn position_error(&self) -> Option<Angle> {
+        self.position_target
+            .map(|target| target - self.output_position())
+    }
+
+    /// Runs one step of the position loop when holding a target with
+    /// [`PositionController::Pid`]. Does nothing otherwise.
+    pub fn update_position(&mut self) {
+        let (Some(target), PositionController::Pid(mut pid)) =
+            (self.position_target, self.position_controller)
+        else {
+            return;
+        };
+
+        let now = clock::now();
+        let dt = self
+            .position_updated
+            .map_or(POSITION_PERIOD, |updated| now.duration_since(updated));
+        if dt.is_zero() {
+            return;
+        }
+        self.position_updated = Some(now);
+
+        let error = (target - self.output_position()).as_degrees();
+        let voltage = pid
+            .output(error, dt)
+            .clamp(-Motor::V5_MAX_VOLTAGE, Motor::V5_MAX_VOLTAGE);
+        self.position_controller = PositionController::Pid(pid);
+
+        self.apply_voltage(voltage);
+    }
+
+    /// Zeroes the group's position at the motors' current positions.
+    pub fn tare(&mut self) {
+        for (motor, zero) in self.motors.iter().zip(&mut self.zeros) {
+            if let Some(position) = motor.position() {
+                *zero = position;
+            }
+        }
+
+        if self.position_target.is_some() {
+            self.hold();
+        }
+    }
+
     /// Set the brake mode for all motors in the group
+    ///
+    /// Cancels any position target.
     pub fn brake(&mut self, brake: BrakeMode) {
+        self.position_target = None;
         for motor in self.motors.iter_mut() {
-            _ = motor.brake(brake);
+            motor.brake(brake);
         }
     }
 
@@ -63,7 +519,7 @@ impl MotorGroup {
     pub fn voltage(&self) -> f64 {
         let mut voltages = Vec::new();
         for motor in self.motors.iter() {
-            if let Ok(voltage) = motor.voltage() {
+            if let Some(voltage) = motor.voltage() {
                 voltages.push(voltage);
             }
         }
@@ -74,15 +530,99 @@ impl MotorGroup {
     pub fn velocity(&self) -> f64 {
         let mut velocities = Vec::new();
         for motor in self.motors.iter() {
-            if let Ok(velocity) = motor.velocity() {
+            if let Some(velocity) = motor.velocity() {
                 velocities.push(velocity);
             }
         }
         average(&velocities)
     }
 
+    /// Compute the average position of all motors in the group since the
+    /// last tare
+    pub fn position(&self) -> Angle {
+        let mut positions = Vec::new();
+        for (motor, zero) in self.motors.iter().zip(&self.zeros) {
+            if let Some(position) = motor.position() {
+                positions.push((position - *zero).as_radians());
+            }
+        }
+        Angle::from_radians(average(&positions))
+    }
+
+    /// Compute the average position of the output since the last tare,
+    /// through the gear ratio
+    pub fn output_position(&self) -> Angle {
+        self.position() * self.gear_ratio
+    }
+
+    /// Reads the health of every motor in the group.
+    pub fn health(&self) -> Vec<MotorHealth> {
+        self.motors
+            .iter()
+            .map(|motor| MotorHealth::read(motor.as_ref()))
+            .collect()
+    }
+
+    /// Returns `true` if every motor is connected, not hot and has no faults.
+    pub fn is_healthy(&self) -> bool {
+        self.health().iter().all(MotorHealth::is_healthy)
+    }
+
+    /// Reads the health of every motor, logging a warning for each motor that
+    /// disconnected, faulted or became hot since the last check, and a note
+    /// when it recovers.
+    ///
+    /// Call this periodically (e.g. from the driver loop, or before a match)
+    /// so an unplugged motor is noticed when it happens.
+    pub fn check_health(&mut self) -> Vec<MotorHealth> {
+        let health = self.health();
+
+        for (i, (prev, now)) in self.health.iter().zip(&health).enumerate() {
+            let name = self.name;
+
+            if prev.connected != now.connected {
+                if now.connected {
+                    info!("{name} motor {i} reconnected");
+                } else {
+                    warn!("{name} motor {i} disconnected");
+                }
+            }
+
+            // Readings of a disconnected motor are meaningless
+            if !now.connected {
+                continue;
+            }
+
+            if prev.faults != now.faults {
+                if now.faults.any() {
+                    warn!("{name} motor {i} faulted: {:?}", now.faults);
+                } else {
+                    info!("{name} motor {i} faults cleared");
+                }
+            }
+
+            if prev.is_hot() != now.is_hot() {
+                let temperature = now.temperature.unwrap_or_default();
+                if now.is_hot() {
+                    warn!("{name} motor {i} is hot ({temperature:.0}°C)");
+                } else {
+                    info!("{name} motor {i} cooled down ({temperature:.0}°C)");
+                }
+            }
+        }
+
+        self.health.clone_from(&health);
+        health
+    }
+
+    /// Returns handles to the motors for reading them alongside the group,
+    /// e.g. from a [`MotorEncoder`](super::encoder::MotorEncoder).
+    pub(crate) fn shared_motors(&self) -> Vec<Rc<RefCell<dyn MotorDevice>>> {
+        self.shared.clone()
+    }
+
     /// Get a mutable iterator over the motors
-    pub fn iter_mut(&mut self) -> core::slice::IterMut<'_, Motor> {
+    pub fn iter_mut(&mut self) -> core::slice::IterMut<'_, Box<dyn MotorDevice>> {
         self.motors.iter_mut()
     }
 }
@@ -105,13 +645,13 @@ impl MotorController {
             ks,
             kv,
             ka,
-            time: Instant::now(),
+            time: clock::now(),
         }
     }
 
     /// Compute the voltage output for a motor based on a target RPM, actual RPM, and acceleration
     pub fn output(&mut self, target_rpm: f64, actual_rpm: f64, acceleration: f64) -> f64 {
-        let now = Instant::now();
+        let now = clock::now();
         let dt = now.duration_since(self.time);
         self.time = now;
 
@@ -130,3 +670,181 @@ impl MotorController {
         ff + pid
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hardware::{
+        battery::BatteryParameters,
+        mock::{MockBattery, MockMotor},
+    };
+
+    #[test]
+    fn averages_skip_disconnected_motors() {
+        let motors = [MockMotor::new(), MockMotor::new(), MockMotor::new()];
+        for (motor, rpm) in motors.iter().zip([100.0, 200.0, 900.0]) {
+            motor.update(|state| {
+                state.velocity = rpm;
+                state.position = Angle::from_degrees(rpm);
+            });
+        }
+        motors[2].update(|state| state.connected = false);
+
+        let group = MotorGroup::new(motors.to_vec(), None);
+
+        assert_eq!(group.velocity(), 150.0);
+        assert!((group.position().as_degrees() - 150.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn commands_reach_every_motor() {
+        let motors = [MockMotor::new(), MockMotor::new()];
+        let mut group = MotorGroup::new(motors.to_vec(), None);
+
+        group.set_voltage(6.0);
+        assert!(motors.iter().all(|motor| motor.state().voltage == 6.0));
+
+        group.brake(BrakeMode::Hold);
+        assert!(motors.iter().all(|motor| motor.state().brake == Some(BrakeMode::Hold)));
+    }
+
+    #[test]
+    fn reports_disconnected_and_faulted_motors() {
+        let motors = [MockMotor::new(), MockMotor::new(), MockMotor::new()];
+        let mut group = MotorGroup::new(motors.to_vec(), None);
+        assert!(group.check_health().iter().all(MotorHealth::is_healthy));
+
+        motors[0].update(|state| state.connected = false);
+        motors[1].update(|state| state.faults.over_current = true);
+        motors[2].update(|state| state.temperature = HOT_TEMPERATURE);
+
+        let health = group.check_health();
+        assert!(!health[0].connected);
+        assert_eq!(health[0].temperature, None);
+        assert!(health[1].connected && health[1].faults.over_current);
+        assert!(health[2].is_hot());
+        assert!(!group.is_healthy());
+
+        for motor in &motors {
+            motor.update(|state| *state = Default::default());
+        }
+        assert!(group.check_health().iter().all(MotorHealth::is_healthy));
+    }
+
+    #[test]
+    fn derating_moves_load_off_hot_motor() {
+        let motors = [(); 5].map(|_| MockMotor::new());
+        motors[0].update(|state| state.temperature = HOT_TEMPERATURE);
+        motors[1].update(|state| state.temperature = 50.0);
+
+        let mut group = MotorGroup::new(motors.to_vec(), None);
+        group.set_derating(Some(DeratingParameters::default()));
+        group.set_voltage(8.0);
+
+        // Every motor keeps the same voltage, and only the hot ones draw less
+        // current
+        assert!(motors.iter().all(|motor| motor.state().voltage == 8.0));
+        assert_eq!(motors[0].state().current_limit, 1.25);
+        assert!((motors[1].state().current_limit - 1.875).abs() < 1e-9);
+        assert!(motors[2..].iter().all(|motor| motor.state().current_limit == 2.5));
+
+        group.set_derating(None);
+        assert!(motors.iter().all(|motor| motor.state().current_limit == 2.5));
+    }
+
+    #[test]
+    fn derating_keeps_built_in_velocity_setpoints_equal() {
+        let motors = [MockMotor::new(), MockMotor::new()];
+        motors[0].update(|state| state.temperature = HOT_TEMPERATURE);
+
+        let mut group = MotorGroup::new(motors.to_vec(), None);
+        group.set_derating(Some(DeratingParameters::default()));
+        group.set_velocity(300.0);
+
+        // Only the hot motor's current limit is lowered
+        assert!(motors.iter().all(|motor| motor.state().target_velocity == Some(300)));
+        assert_eq!(motors[0].state().current_limit, 1.25);
+        assert_eq!(motors[1].state().current_limit, 2.5);
+    }
+
+    #[test]
+    fn battery_compensation_scales_voltage() {
+        let motors = [MockMotor::new(), MockMotor::new()];
+        let battery = MockBattery::new();
+        battery.set_voltage(Some(11.2));
+
+        let mut group = MotorGroup::new(motors.to_vec(), None);
+        group.set_battery_compensation(Some(Battery::with_device(
+            battery,
+            BatteryParameters {
+                max_scale: 2.0,
+                ..Default::default()
+            },
+        )));
+        group.set_voltage(7.0);
+
+        assert!(motors.iter().all(|motor| (motor.state().voltage - 8.0).abs() < 1e-9));
+    }
+
+    #[test]
+    fn position_is_tared_and_geared() {
+        let motors = [MockMotor::new(), MockMotor::new()];
+        for (motor, degrees) in motors.iter().zip([90.0, 110.0]) {
+            motor.update(|state| state.position = Angle::from_degrees(degrees));
+        }
+
+        let mut group = MotorGroup::new(motors.to_vec(), None);
+        group.set_gear_ratio(1.0 / 3.0);
+        assert!((group.output_position().as_degrees() - 100.0 / 3.0).abs() < 1e-9);
+
+        group.tare();
+        for motor in &motors {
+            motor.update(|state| state.position += Angle::from_degrees(90.0));
+        }
+        assert!((group.position().as_degrees() - 90.0).abs() < 1e-9);
+        assert!((group.output_position().as_degrees() - 30.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn built_in_targets_are_sent_in_motor_rotation() {
+        let motors = [MockMotor::new(), MockMotor::new()];
+        motors[1].update(|state| state.position = Angle::from_degrees(20.0));
+
+        let mut group = MotorGroup::new(motors.to_vec(), None);
+        group
+            .set_gear_ratio(0.5)
+            .set_position_controller(PositionController::BuiltIn { rpm: 100 });
+        group.tare();
+        group.set_position_target(Angle::from_degrees(45.0));
+        group.move_relative(Angle::from_degrees(45.0));
+
+        // Each motor moves 180° from where it was tared
+        for (motor, zero) in motors.iter().zip([0.0, 20.0]) {
+            let (target, rpm) = motor.state().target_position.unwrap();
+            assert!((target.as_degrees() - (zero + 180.0)).abs() < 1e-9);
+            assert_eq!(rpm, 100);
+        }
+        assert!((group.position_target().unwrap().as_degrees() - 90.0).abs() < 1e-9);
+
+        group.set_voltage(0.0);
+        assert_eq!(group.position_target(), None);
+    }
+
+    #[test]
+    fn pid_drives_toward_target() {
+        clock::use_virtual_time();
+        let motor = MockMotor::new();
+        let mut group = MotorGroup::new(vec![motor.clone()], None);
+        group.set_position_controller(PositionController::Pid(Pid::new(0.1, 0.0, 0.0, 0.0)));
+
+        group.set_position_target(Angle::from_degrees(30.0));
+        assert!((motor.state().voltage - 3.0).abs() < 1e-9);
+
+        motor.update(|state| state.position = Angle::from_degrees(90.0));
+        clock::advance(Duration::from_millis(10));
+        group.update_position();
+        clock::use_real_time();
+
+        assert!((motor.state().voltage + 6.0).abs() < 1e-9);
+    }
+}
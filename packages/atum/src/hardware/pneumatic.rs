@@ -0,0 +1,349 @@
+//! Pneumatics
+//!
+//! [`Pneumatic`] wraps the solenoid valve of a pneumatic cylinder so robots
+//! command it as extended or retracted instead of as a logic level. Whether
+//! extended is the high or the low level is configured once per cylinder.
+//!
+//! Cylinders count their actuations and can draw from a shared
+//! [`AirSupply`], which estimates how much air is left in the robot's tanks
+//! and warns when it runs low. [`log_diagnostics`] logs where every
+//! cylinder is and how much air is left.
+
+use std::{cell::Cell, fmt::Display, rc::Rc, time::Duration};
+
+use log::{info, warn};
+use vexide::{
+    adi::{AdiPort, digital::LogicLevel},
+    prelude::AdiDigitalOut,
+};
+
+use super::devices::DigitalOutDevice;
+use crate::clock::sleep;
+
+/// Fraction of the air supply below which it is considered low.
+const LOW_AIR: f64 = 0.2;
+
+/// Position of a pneumatic cylinder.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PneumaticState {
+    #[default]
+    Retracted,
+    Extended,
+}
+
+impl PneumaticState {
+    /// Returns the opposite state.
+    pub fn toggled(self) -> Self {
+        match self {
+            Self::Retracted => Self::Extended,
+            Self::Extended => Self::Retracted,
+        }
+    }
+}
+
+/// Estimated air left in the robot's tanks, shared by every cylinder they
+/// feed.
+///
+/// Air is measured in actuations of a cylinder with an air use of `1.0`
+/// (see [`Pneumatic::set_air_use`]). Clones share the same estimate.
+#[derive(Debug, Clone)]
+pub struct AirSupply {
+    capacity: f64,
+    used: Rc<Cell<f64>>,
+    low: Rc<Cell<bool>>,
+}
+
+impl AirSupply {
+    /// Creates a full supply good for `capacity` actuations.
+    pub fn new(capacity: f64) -> Self {
+        Self {
+            capacity,
+            used: Rc::new(Cell::new(0.0)),
+            low: Rc::new(Cell::new(false)),
+        }
+    }
+
+    /// Returns the number of actuations a full supply is good for.
+    pub fn capacity(&self) -> f64 {
+        self.capacity
+    }
+
+    /// Returns the estimated number of actuations left.
+    pub fn remaining(&self) -> f64 {
+        (self.capacity - self.used.get()).max(0.0)
+    }
+
+    /// Returns the estimated fraction of air left, from `0.0` to `1.0`.
+    pub fn fraction(&self) -> f64 {
+        if self.capacity > 0.0 {
+            self.remaining() / self.capacity
+        } else {
+            0.0
+        }
+    }
+
+    /// Returns `true` if less than 20% of the air is estimated to be left.
+    pub fn is_low(&self) -> bool {
+        self.fraction() < LOW_AIR
+    }
+
+    /// Marks the tanks as full again, e.g. after pumping them up.
+    pub fn refill(&self) {
+        self.used.set(0.0);
+        self.low.set(false);
+    }
+
+    /// Uses `amount` of air for an actuation of `name`, warning the first time
+    /// the supply runs low.
+    fn consume(&self, amount: f64, name: &str) {
+        self.used.set(self.used.get() + amount);
+
+        if self.is_low() && !self.low.get() {
+            warn!(
+                "Air low after {name} actuated, about {:.0} actuations left",
+                self.remaining()
+            );
+            self.low.set(true);
+        }
+    }
+}
+
+/// Snapshot of a cylinder for logging.
+#[derive(Debug, Clone, Copy)]
+pub struct PneumaticDiagnostic {
+    /// Name of the cylinder.
+    pub name: &'static str,
+
+    /// Commanded position of the cylinder.
+    pub state: PneumaticState,
+
+    /// Number of times the cylinder changed position.
+    pub actuations: u32,
+
+    /// Estimated actuations left in the cylinder's air supply, if it has one.
+    pub air_remaining: Option<f64>,
+}
+
+impl Display for PneumaticDiagnostic {
+    /// Formats the diagnostic for logging.
+    ///
+    /// Output format:
+    ///
+    /// `name: state, n actuations, air remaining`
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{}: {:?}, {} actuations",
+            self.name, self.state, self.actuations
+        )?;
+
+        if let Some(air) = self.air_remaining {
+            write!(f, ", ~{air:.0} actuations of air left")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A pneumatic cylinder driven by a solenoid valve.
+///
+/// Cylinders start retracted.
+pub struct Pneumatic {
+    device: Box<dyn DigitalOutDevice>,
+    name: &'static str,
+
+    /// Whether the cylinder is extended by driving the valve low.
+    inverted: bool,
+
+    state: PneumaticState,
+    actuations: u32,
+    air: Option<AirSupply>,
+
+    /// Air used per actuation, relative to the supply's unit.
+    air_use: f64,
+}
+
+impl Pneumatic {
+    /// Creates a cylinder on an ADI port. With `inverted`, the cylinder is
+    /// extended by driving the port low.
+    pub fn new(name: &'static str, port: AdiPort, inverted: bool) -> Self {
+        let level = if inverted {
+            LogicLevel::High
+        } else {
+            LogicLevel::Low
+        };
+
+        Self::with_device(
+            name,
+            AdiDigitalOut::with_initial_level(port, level),
+            inverted,
+        )
+    }
+
+    /// Creates a cylinder on any digital output, e.g. a
+    /// [`MockDigitalOut`](super::mock::MockDigitalOut).
+    pub fn with_device(
+        name: &'static str,
+        device: impl DigitalOutDevice + 'static,
+        inverted: bool,
+    ) -> Self {
+        let mut pneumatic = Self {
+            device: Box::new(device),
+            name,
+            inverted,
+            state: PneumaticState::Retracted,
+            actuations: 0,
+            air: None,
+            air_use: 1.0,
+        };
+        pneumatic.write();
+        pneumatic
+    }
+
+    /// Sets the air supply the cylinder draws from, or stops tracking its
+    /// air use with `None`.
+    pub fn set_air_supply(&mut self, air: Option<AirSupply>) -> &mut Self {
+        self.air = air;
+        self
+    }
+
+    /// Sets the air used per actuation, relative to the supply's unit, e.g.
+    /// `2.0` for a cylinder twice the size of the one the supply was
+    /// measured with.
+    pub fn set_air_use(&mut self, air_use: f64) -> &mut Self {
+        self.air_use = air_use;
+        self
+    }
+
+    /// Returns the name of the cylinder.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the commanded position of the cylinder.
+    pub fn state(&self) -> PneumaticState {
+        self.state
+    }
+
+    /// Returns `true` if the cylinder is commanded extended.
+    pub fn is_extended(&self) -> bool {
+        self.state == PneumaticState::Extended
+    }
+
+    /// Returns the number of times the cylinder changed position.
+    pub fn actuations(&self) -> u32 {
+        self.actuations
+    }
+
+    /// Moves the cylinder to `state`. Does nothing if it is already there.
+    pub fn set_state(&mut self, state: PneumaticState) {
+        if state == self.state {
+            return;
+        }
+
+        self.state = state;
+        self.actuations += 1;
+        self.write();
+
+        if let Some(air) = &self.air {
+            air.consume(self.air_use, self.name);
+        }
+    }
+
+    /// Extends the cylinder if `extended` is `true`, and retracts it
+    /// otherwise.
+    pub fn set_extended(&mut self, extended: bool) {
+        self.set_state(if extended {
+            PneumaticState::Extended
+        } else {
+            PneumaticState::Retracted
+        });
+    }
+
+    /// Extends the cylinder.
+    pub fn extend(&mut self) {
+        self.set_state(PneumaticState::Extended);
+    }
+
+    /// Retracts the cylinder.
+    pub fn retract(&mut self) {
+        self.set_state(PneumaticState::Retracted);
+    }
+
+    /// Moves the cylinder to its other position.
+    pub fn toggle(&mut self) {
+        self.set_state(self.state.toggled());
+    }
+
+    /// Moves the cylinder to its other position for `duration`, then back.
+    pub async fn pulse(&mut self, duration: Duration) {
+        self.toggle();
+        sleep(duration).await;
+        self.toggle();
+    }
+
+    /// Returns a snapshot of the cylinder for logging.
+    pub fn diagnostic(&self) -> PneumaticDiagnostic {
+        PneumaticDiagnostic {
+            name: self.name,
+            state: self.state,
+            actuations: self.actuations,
+            air_remaining: self.air.as_ref().map(AirSupply::remaining),
+        }
+    }
+
+    fn write(&mut self) {
+        let high = self.is_extended() != self.inverted;
+        self.device.set_level(high);
+    }
+}
+
+/// Logs the diagnostic of each cylinder, e.g. at the end of autonomous.
+pub fn log_diagnostics<'a>(pneumatics: impl IntoIterator<Item = &'a Pneumatic>) {
+    for pneumatic in pneumatics {
+        info!("{}", pneumatic.diagnostic());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hardware::mock::MockDigitalOut;
+
+    #[test]
+    fn inversion_flips_the_level() {
+        let normal = MockDigitalOut::new();
+        let inverted = MockDigitalOut::new();
+        let mut a = Pneumatic::with_device("a", normal.clone(), false);
+        let mut b = Pneumatic::with_device("b", inverted.clone(), true);
+        assert!(!normal.is_high() && inverted.is_high());
+
+        a.extend();
+        b.extend();
+        assert!(normal.is_high() && !inverted.is_high());
+    }
+
+    #[test]
+    fn counts_actuations_and_air() {
+        let air = AirSupply::new(10.0);
+        let mut pneumatic = Pneumatic::with_device("lift", MockDigitalOut::new(), false);
+        pneumatic.set_air_supply(Some(air.clone())).set_air_use(2.0);
+
+        pneumatic.extend();
+        // Commanding the same position again does not actuate the cylinder
+        pneumatic.extend();
+        pneumatic.toggle();
+        pneumatic.set_extended(true);
+
+        assert_eq!(pneumatic.actuations(), 3);
+        assert_eq!(air.remaining(), 4.0);
+        assert!(!air.is_low());
+
+        pneumatic.toggle();
+        assert!(air.is_low());
+        assert_eq!(pneumatic.diagnostic().air_remaining, Some(2.0));
+
+        air.refill();
+        assert_eq!(air.remaining(), 10.0);
+    }
+}
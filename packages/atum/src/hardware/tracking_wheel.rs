@@ -6,17 +6,20 @@ use vexide::{
     prelude::AdiEncoder,
 };
 
+use super::encoder::Encoder;
 use crate::localization::vec2::Vec2;
 
 /// Represents a passive tracking wheel used for robot localization.
 ///
 /// A tracking wheel measures linear displacement using an encoder
-/// attached to a freely rotating wheel. The wheel's position relative
+/// attached to a freely rotating wheel. Any [`Encoder`] can be used, so the
+/// wheel may be read from an ADI encoder, a smart-port rotation sensor or a
+/// motor's integrated encoder. The wheel's position relative
 /// to the robot and its orientation are used by the odometry system
 /// to estimate the robot's movement.
 pub struct TrackingWheel {
     /// Encoder attached to the tracking wheel.
-    encoder: AdiEncoder<4096>,
+    encoder: Box<dyn Encoder>,
 
     /// Circumference of the tracking wheel.
     wheel_circum: f64,
@@ -44,11 +47,29 @@ impl TrackingWheel {
         from_center: Vec2<f64>,
         angle: Angle,
     ) -> Self {
-        let encoder = AdiEncoder::new(top_port, bottom_port);
+        Self::with_encoder(
+            AdiEncoder::<4096>::new(top_port, bottom_port),
+            wheel_diameter,
+            from_center,
+            angle,
+        )
+    }
+
+    /// Creates a new tracking wheel read from any encoder.
+    ///
+    /// Use a [`RotationSensor`](vexide::prelude::RotationSensor) to free up
+    /// ADI ports, or a [`MotorEncoder`](super::encoder::MotorEncoder) to track
+    /// with a motor's integrated encoder.
+    pub fn with_encoder(
+        encoder: impl Encoder + 'static,
+        wheel_diameter: f64,
+        from_center: Vec2<f64>,
+        angle: Angle,
+    ) -> Self {
         let prev_position = encoder.position().unwrap_or_default();
 
         Self {
-            encoder,
+            encoder: Box::new(encoder),
             wheel_circum: wheel_diameter * PI,
             from_center,
             angle,
@@ -66,10 +87,28 @@ impl TrackingWheel {
         self.angle
     }
 
+    /// Returns the diameter of the wheel.
+    pub fn diameter(&self) -> f64 {
+        self.wheel_circum / PI
+    }
+
+    /// Returns the raw encoder position.
+    ///
+    /// Unlike [`Self::traveled`], this does not consume the change since the
+    /// previous update.
+    pub fn position(&self) -> Angle {
+        self.encoder.position().unwrap_or_default()
+    }
+
+    /// Returns the total distance traveled by the tracking wheel.
+    pub fn distance(&self) -> f64 {
+        self.wheel_circum * self.position().as_turns()
+    }
+
     /// Returns the distance traveled by the tracking wheel
     /// since the previous update.
     pub fn traveled(&mut self) -> f64 {
-        let position = self.encoder.position().unwrap_or_default();
+        let position = self.position();
         let change = position - self.prev_position;
         self.prev_position = position;
 
@@ -3,7 +3,9 @@ use std::ops::Range;
 use log::debug;
 use vexide::{math::Angle, prelude::DistanceSensor, smart::{SmartPort, distance::DistanceObjectError}};
 
-use crate::localization::vec2::Vec2;
+use super::devices::DistanceDevice;
+
+use crate::localization::{pose::Pose, vec2::Vec2};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Wall {
@@ -20,7 +22,7 @@ pub struct WallHit {
 }
 
 pub struct WallDistanceSensor {
-    sensor: DistanceSensor,
+    sensor: Box<dyn DistanceDevice>,
     offset: Vec2<f64>,
     angle: Angle,
     wall_size: Range<u32>,
@@ -41,9 +43,19 @@ impl WallDistanceSensor {
         offset: Vec2<f64>,
         angle: Angle,
         wall_size: Range<u32>,
+    ) -> Self {
+        Self::with_device(DistanceSensor::new(port), offset, angle, wall_size)
+    }
+
+    /// Creates a new Localization Sensor from any distance sensor device.
+    pub fn with_device(
+        sensor: impl DistanceDevice + 'static,
+        offset: Vec2<f64>,
+        angle: Angle,
+        wall_size: Range<u32>,
     ) -> Self {
         Self {
-            sensor: DistanceSensor::new(port),
+            sensor: Box::new(sensor),
             offset,
             angle,
             wall_size,
@@ -54,8 +66,8 @@ impl WallDistanceSensor {
         robot_heading + self.angle
     }
 
-    pub fn world_position(&self, robot_position: Vec2<f64>,robot_heading: Angle) -> Vec2<f64> {
-        robot_position + self.offset.rotated(robot_heading.as_radians())
+    pub fn world_position(&self, robot_pose: Pose) -> Vec2<f64> {
+        robot_pose.to_field(self.offset)
     }
 
     pub fn distance(&self) -> Result<Option<f64>, DistanceObjectError> {
@@ -85,102 +97,210 @@ impl WallDistanceSensor {
 
     pub fn predicted_hit(
         &self,
-        robot_position: Vec2<f64>,
-        robot_heading: Angle,
+        robot_pose: Pose,
         field_size: f64,
         max_dist: f64,
     ) -> Option<WallHit> {
-        let origin = self.world_position(robot_position, robot_heading);
-        let theta = self.world_angle(robot_heading).as_radians();
-
-        let dx = theta.cos();
-        let dy = theta.sin();
-
-        let mut wall_hit: Option<WallHit> = None;
-
-        if dx.abs() > 1e-6 {
-            let t = (0.0 - origin.x) / dx;
-            if t > 0.0 && t <= max_dist {
-                let y_hit = origin.y + t * dy;
-                if y_hit >= 0.0 && y_hit <= field_size {
-                    wall_hit = Some(WallHit {
-                        distance: t,
-                        wall: Wall::Left,
-                    });
-                }
+        raycast(
+            self.world_position(robot_pose),
+            self.world_angle(robot_pose.h),
+            field_size,
+            max_dist,
+        )
+    }
+
+    pub fn measurement_error(
+        &self,
+        robot_pose: Pose,
+        field_size: f64,
+        max_dist: f64,
+    ) -> Result<Option<(WallHit, f64)>, DistanceObjectError> {
+        let measured = match self.distance()? {
+            Some(d) => d,
+            None => return Ok(None),
+        };
+
+        let predicted = match self.predicted_hit(robot_pose, field_size, max_dist) {
+            Some(hit) => hit,
+            None => return Ok(None),
+        };
+
+        Ok(Some((predicted, measured - predicted.distance)))
+    }    
+}
+
+/// Casts a ray from `origin` in `direction` and returns the first field wall it hits.
+///
+/// The field is the square `[0, field_size] x [0, field_size]`. Hits further
+/// than `max_dist` are ignored.
+pub fn raycast(
+    origin: Vec2<f64>,
+    direction: Angle,
+    field_size: f64,
+    max_dist: f64,
+) -> Option<WallHit> {
+    let theta = direction.as_radians();
+
+    let dx = theta.cos();
+    let dy = theta.sin();
+
+    let mut wall_hit: Option<WallHit> = None;
+
+    if dx.abs() > 1e-6 {
+        let t = (0.0 - origin.x) / dx;
+        if t > 0.0 && t <= max_dist {
+            let y_hit = origin.y + t * dy;
+            if y_hit >= 0.0 && y_hit <= field_size {
+                wall_hit = Some(WallHit {
+                    distance: t,
+                    wall: Wall::Left,
+                });
             }
+        }
 
-            let t = (field_size - origin.x) / dx;
-            if t > 0.0 && t <= max_dist {
-                let y_hit = origin.y + t * dy;
-                if y_hit >= 0.0 && y_hit <= field_size {
-                    match wall_hit {
-                        Some(hit) if t >= hit.distance => {}
-                        _ => {
-                            wall_hit = Some(WallHit {
-                                distance: t,
-                                wall: Wall::Right,
-                            });
-                        }
+        let t = (field_size - origin.x) / dx;
+        if t > 0.0 && t <= max_dist {
+            let y_hit = origin.y + t * dy;
+            if y_hit >= 0.0 && y_hit <= field_size {
+                match wall_hit {
+                    Some(hit) if t >= hit.distance => {}
+                    _ => {
+                        wall_hit = Some(WallHit {
+                            distance: t,
+                            wall: Wall::Right,
+                        });
                     }
                 }
             }
         }
+    }
 
-        if dy.abs() > 1e-6 {
-            let t = (0.0 - origin.y) / dy;
-            if t > 0.0 && t <= max_dist {
-                let x_hit = origin.x + t * dx;
-                if x_hit >= 0.0 && x_hit <= field_size {
-                    match wall_hit {
-                        Some(hit) if t >= hit.distance => {}
-                        _ => {
-                            wall_hit = Some(WallHit {
-                                distance: t,
-                                wall: Wall::Bottom,
-                            });
-                        }
+    if dy.abs() > 1e-6 {
+        let t = (0.0 - origin.y) / dy;
+        if t > 0.0 && t <= max_dist {
+            let x_hit = origin.x + t * dx;
+            if x_hit >= 0.0 && x_hit <= field_size {
+                match wall_hit {
+                    Some(hit) if t >= hit.distance => {}
+                    _ => {
+                        wall_hit = Some(WallHit {
+                            distance: t,
+                            wall: Wall::Bottom,
+                        });
                     }
                 }
             }
+        }
 
-            let t = (field_size - origin.y) / dy;
-            if t > 0.0 && t <= max_dist {
-                let x_hit = origin.x + t * dx;
-                if x_hit >= 0.0 && x_hit <= field_size {
-                    match wall_hit {
-                        Some(hit) if t >= hit.distance => {}
-                        _ => {
-                            wall_hit = Some(WallHit {
-                                distance: t,
-                                wall: Wall::Top,
-                            });
-                        }
+        let t = (field_size - origin.y) / dy;
+        if t > 0.0 && t <= max_dist {
+            let x_hit = origin.x + t * dx;
+            if x_hit >= 0.0 && x_hit <= field_size {
+                match wall_hit {
+                    Some(hit) if t >= hit.distance => {}
+                    _ => {
+                        wall_hit = Some(WallHit {
+                            distance: t,
+                            wall: Wall::Top,
+                        });
                     }
                 }
             }
         }
+    }
 
-        wall_hit
+    wall_hit
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hardware::mock::MockDistanceSensor;
+
+    const FIELD_SIZE: f64 = 144.0;
+    const MAX_DIST: f64 = 300.0;
+
+    fn sensor(offset: Vec2<f64>, degrees: f64) -> WallDistanceSensor {
+        WallDistanceSensor::with_device(
+            MockDistanceSensor::new(),
+            offset,
+            Angle::from_degrees(degrees),
+            0..400,
+        )
     }
 
-    pub fn measurement_error(
-        &self,
-        robot_position: Vec2<f64>,
-        robot_heading: Angle,
-        field_size: f64,
-        max_dist: f64,
-    ) -> Result<Option<(WallHit, f64)>, DistanceObjectError> {
-        let measured = match self.distance()? {
-            Some(d) => d,
-            None => return Ok(None),
-        };
+    fn hit(sensor: &WallDistanceSensor, pose: Pose) -> (Wall, f64) {
+        let hit = sensor.predicted_hit(pose, FIELD_SIZE, MAX_DIST).unwrap();
+        (hit.wall, hit.distance)
+    }
 
-        let predicted = match self.predicted_hit(robot_position, robot_heading, field_size, max_dist) {
-            Some(hit) => hit,
-            None => return Ok(None),
-        };
+    fn assert_hit(actual: (Wall, f64), wall: Wall, distance: f64) {
+        assert_eq!(actual.0, wall);
+        assert!(
+            (actual.1 - distance).abs() < 1e-6,
+            "expected {distance}, got {}",
+            actual.1
+        );
+    }
 
-        Ok(Some((predicted, measured - predicted.distance)))
-    }    
+    #[test]
+    fn hits_each_wall() {
+        let pose = Pose::new(72.0, 36.0, Angle::ZERO);
+        let offset = Vec2::new(2.0, 0.0);
+
+        assert_hit(hit(&sensor(offset, 0.0), pose), Wall::Right, 70.0);
+        assert_hit(hit(&sensor(offset, 180.0), pose), Wall::Left, 74.0);
+        assert_hit(hit(&sensor(offset, 90.0), pose), Wall::Top, 108.0);
+        assert_hit(hit(&sensor(offset, -90.0), pose), Wall::Bottom, 36.0);
+    }
+
+    #[test]
+    fn rotates_offset_with_robot() {
+        // Facing +y, the sensor sits 2" ahead of the center at (72, 38)
+        let pose = Pose::new(72.0, 36.0, Angle::QUARTER_TURN);
+        let offset = Vec2::new(2.0, 0.0);
+
+        assert_hit(hit(&sensor(offset, 0.0), pose), Wall::Top, 106.0);
+        assert_hit(hit(&sensor(offset, 90.0), pose), Wall::Left, 72.0);
+        assert_hit(hit(&sensor(offset, -90.0), pose), Wall::Right, 72.0);
+        assert_hit(hit(&sensor(offset, 180.0), pose), Wall::Bottom, 38.0);
+    }
+
+    #[test]
+    fn corners_resolve_to_nearest_wall() {
+        let pose = Pose::new(72.0, 72.0, Angle::ZERO);
+        let center = Vec2::new(0.0, 0.0);
+        let diagonal = 72.0 * 2.0_f64.sqrt();
+
+        // Exactly into a corner, either adjacent wall may be reported
+        for (degrees, walls) in [
+            (45.0, [Wall::Right, Wall::Top]),
+            (135.0, [Wall::Left, Wall::Top]),
+            (-135.0, [Wall::Left, Wall::Bottom]),
+            (-45.0, [Wall::Right, Wall::Bottom]),
+        ] {
+            let (wall, distance) = hit(&sensor(center, degrees), pose);
+            assert!(walls.contains(&wall), "{degrees} deg hit {wall:?}");
+            assert!((distance - diagonal).abs() < 1e-6);
+        }
+
+        // Just either side of a corner
+        let (wall, _) = hit(&sensor(center, 44.9), pose);
+        assert_eq!(wall, Wall::Right);
+        let (wall, _) = hit(&sensor(center, 45.1), pose);
+        assert_eq!(wall, Wall::Top);
+        let (wall, _) = hit(&sensor(center, -135.1), pose);
+        assert_eq!(wall, Wall::Left);
+        let (wall, _) = hit(&sensor(center, -134.9), pose);
+        assert_eq!(wall, Wall::Bottom);
+    }
+
+    #[test]
+    fn ignores_walls_out_of_range() {
+        let pose = Pose::new(72.0, 36.0, Angle::ZERO);
+        let sensor = sensor(Vec2::new(0.0, 0.0), 90.0);
+
+        assert!(sensor.predicted_hit(pose, FIELD_SIZE, 100.0).is_none());
+        assert!(sensor.predicted_hit(pose, FIELD_SIZE, 108.0).is_some());
+    }
 }
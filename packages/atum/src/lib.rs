@@ -6,10 +6,13 @@
 //! ## Modules
 //!
 //! - [`backend`] – GUI integration
+//! - [`calibration`] – routines for measuring robot constants
+//! - [`clock`] – time source that can run in virtual time
 //! - [`controllers`] – control algorithms
 //! - [`hardware`] – abstraction for motors and sensors
 //! - [`localization`] – odometry and pose representation
 //! - [`motion`] – higher-level motion commands
+//! - [`simulation`] – drivetrain physics for running without a robot
 //! - [`subsystems`] – shared subsystems across robots
 //! - [`logger`] – console logging utilities
 //! - [`mappings`] – input mappings for controller state
@@ -17,10 +20,13 @@
 //! - [`theme`] – prints a custom startup banner
 
 pub mod backend;
+pub mod calibration;
+pub mod clock;
 pub mod controllers;
 pub mod hardware;
 pub mod localization;
 pub mod motion;
+pub mod simulation;
 pub mod subsystems;
 
 pub mod logger;
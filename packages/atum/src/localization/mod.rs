@@ -5,10 +5,14 @@
 //! - [`rcl`] – pose reset system using distance sensors
 //! - [`odometry`] – pose estimation system
 //! - [`pose`] – robot pose representation
+//! - [`pose_handle`] – shared robot pose with recorded corrections
 //! - [`vec2`] – 2D vector math utilities
+//! - [`wheel_heading`] – heading from parallel wheels
 
 pub mod rcl;
 pub mod odometry;
 pub mod pose;
+pub mod pose_handle;
 pub mod shape;
 pub mod vec2;
+pub mod wheel_heading;
@@ -1,7 +1,7 @@
 //! Odometry System
 //!
 //! This module continuously estimates the robot's pose on the field using
-//! two tracking wheels and an IMU.
+//! tracking wheels and, optionally, an IMU.
 //!
 //! The estimated pose consists of:
 //!
@@ -16,34 +16,336 @@
 //! tracking wheels and IMU, computes the robot's motion since the last update,
 //! and integrates it into the global pose estimate.
 //!
-//! Two tracking wheels measure motion along known axes relative to the robot.
-//! Using those measurements along with the robot's change in heading, we
-//! reconstruct the robot's translation and rotation over time.
+//! Each tracking wheel measures motion along a known axis relative to the
+//! robot. A wheel with axis `a` and offset `o` from the tracking center
+//! travels
+//!
+//!     s = a · d + dθ (o × a)
+//!
+//! for a robot-frame translation `d` and rotation `dθ`. With the rotation
+//! known from the IMU, two non-parallel wheels are enough to solve for `d`.
+//! Without it, three wheels can solve for both `d` and `dθ`. Any extra wheels
+//! over-determine the system, which is solved by least squares; a wheel that
+//! disagrees with the rest (e.g. lifted or slipping) is rejected.
+//!
+//! Optionally, a pair of parallel wheels provides a second heading estimate,
+//! e.g. the drive motors' encoders (see [`Drivetrain`]). It is used when
+//! every IMU has failed and is otherwise compared against the IMU to catch
+//! drift. With no heading source left and too few wheels to solve for the
+//! rotation, the heading is held and the translation is still tracked.
+//!
+//! Recent poses are kept with their timestamps so that delayed measurements
+//! (e.g. distance sensor readings) can be compared against, and corrected
+//! from, where the robot was when they were captured.
+//!
+//! [`Drivetrain`]: crate::subsystems::drivetrain::Drivetrain
 
 use std::{
-    cell::RefCell,
+    cell::{Cell, RefCell},
+    collections::VecDeque,
     rc::Rc,
     time::{Duration, Instant},
 };
 
-use log::debug;
+use log::{info, warn};
 use vexide::{
-    math::Angle, task::{spawn, Task}, time::sleep
+    math::Angle,
+    task::{Task, spawn},
 };
 
-use super::pose::Pose;
+use super::{pose::Pose, pose_handle::PoseHandle};
 use crate::{
+    clock,
     hardware::{imu::Imu, tracking_wheel::TrackingWheel},
-    localization::vec2::Vec2,
+    localization::{
+        vec2::Vec2,
+        wheel_heading::{HeadingCheck, WheelHeading},
+    },
+    utils::{FixedRate, LoopStats, solve_linear},
 };
 
+/// Number of past poses kept for latency compensation (~1s of updates).
+const HISTORY_LEN: usize = 100;
+
+/// Largest residual (inches per update) a wheel may have before it is
+/// rejected as lifted or slipping.
+const MAX_WHEEL_RESIDUAL: f64 = 0.1;
+
+/// Timing and filtering parameters of the odometry loop.
+#[derive(Debug, Clone, Copy)]
+pub struct OdometryParameters {
+    /// Target time between updates.
+    pub period: Duration,
+
+    /// Updates closer together than this keep the previous velocities, since
+    /// dividing by a tiny `dt` amplifies encoder quantization.
+    pub min_dt: Duration,
+
+    /// Time constant of the low-pass filter on `vf` and `vs`.
+    /// Zero disables filtering.
+    pub linear_filter: Duration,
+
+    /// Time constant of the low-pass filter on `omega`.
+    /// Zero disables filtering.
+    pub angular_filter: Duration,
+}
+
+impl Default for OdometryParameters {
+    fn default() -> Self {
+        Self {
+            period: Duration::from_millis(10),
+            min_dt: Duration::from_millis(2),
+            linear_filter: Duration::from_millis(20),
+            angular_filter: Duration::from_millis(20),
+        }
+    }
+}
+
+/// Estimated acceleration of the robot.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Acceleration {
+    /// Linear acceleration in the robot frame (inches/sec², +x forward).
+    pub linear: Vec2<f64>,
+
+    /// Angular acceleration (radians/sec²).
+    pub angular: f64,
+}
+
+/// First-order low-pass filter step towards `sample` over `dt` seconds.
+fn low_pass(prev: f64, sample: f64, dt: f64, time_constant: Duration) -> f64 {
+    let alpha = dt / (time_constant.as_secs_f64() + dt);
+    prev + alpha * (sample - prev)
+}
+
+/// Measurement geometry of a single tracking wheel.
+#[derive(Debug, Clone, Copy)]
+pub struct WheelGeometry {
+    /// Unit vector along the wheel's measurement axis.
+    pub axis: Vec2<f64>,
+
+    /// Distance the wheel travels per radian of robot rotation (`o × a`).
+    pub lever: f64,
+}
+
+impl WheelGeometry {
+    /// Creates the geometry of a wheel at `from_center` measuring along `angle`.
+    pub fn new(from_center: Vec2<f64>, angle: Angle) -> Self {
+        let axis = Vec2::new(angle.cos(), angle.sin());
+
+        Self {
+            axis,
+            lever: from_center.cross(axis),
+        }
+    }
+}
+
+/// Robot-frame motion reconstructed from tracking wheel measurements.
+#[derive(Debug, Clone)]
+pub struct MotionEstimate {
+    /// Robot-frame translation.
+    pub delta: Vec2<f64>,
+
+    /// Change in heading (radians).
+    pub dh: f64,
+
+    /// Measured minus predicted travel of each wheel.
+    pub residuals: Vec<f64>,
+}
+
+/// Solves for the robot-frame translation, and the rotation if `dh` is
+/// `None`, from the distance `ds` traveled by each wheel.
+///
+/// Only wheels with `used[i] == true` contribute. The normal equations are
+/// solved by least squares, so any number of wheels can be used as long as
+/// they determine the motion. Returns `None` otherwise.
+pub fn solve_motion(
+    wheels: &[WheelGeometry],
+    ds: &[f64],
+    dh: Option<f64>,
+    used: &[bool],
+) -> Option<MotionEstimate> {
+    let unknowns = if dh.is_some() { 2 } else { 3 };
+
+    let mut ata = [[0.0; 3]; 3];
+    let mut atb = [0.0; 3];
+
+    for ((wheel, s), _) in wheels.iter().zip(ds).zip(used).filter(|(_, used)| **used) {
+        let row = [wheel.axis.x, wheel.axis.y, wheel.lever];
+
+        // With a known rotation, move its contribution to the measured side
+        let b = match dh {
+            Some(dh) => s - dh * wheel.lever,
+            None => *s,
+        };
+
+        for r in 0..unknowns {
+            for c in 0..unknowns {
+                ata[r][c] += row[r] * row[c];
+            }
+            atb[r] += row[r] * b;
+        }
+    }
+
+    let (delta, dh) = match dh {
+        Some(dh) => {
+            let x = solve_linear(
+                [[ata[0][0], ata[0][1]], [ata[1][0], ata[1][1]]],
+                [atb[0], atb[1]],
+            )?;
+            (Vec2::new(x[0], x[1]), dh)
+        }
+        None => {
+            let x = solve_linear(ata, atb)?;
+            (Vec2::new(x[0], x[1]), x[2])
+        }
+    };
+
+    let residuals = wheels
+        .iter()
+        .zip(ds)
+        .map(|(wheel, s)| s - (wheel.axis.dot(delta) + dh * wheel.lever))
+        .collect();
+
+    Some(MotionEstimate {
+        delta,
+        dh,
+        residuals,
+    })
+}
+
+/// Advances `prev` by the robot-frame translation `delta` and rotation `dh`
+/// (radians), keeping its velocities.
+///
+/// The translation is rotated into field coordinates at the midpoint
+/// heading, which is exact for straight lines and turns in place and close
+/// for short arcs.
+pub fn integrate(prev: &Pose, delta: Vec2<f64>, dh: f64) -> Pose {
+    let dh = Angle::from_radians(dh);
+    let heading_avg = prev.h + dh / 2.0;
+    let global_delta = delta.rotated(heading_avg.as_radians());
+
+    Pose {
+        x: prev.x + global_delta.x,
+        y: prev.y + global_delta.y,
+        h: prev.h + dh,
+        ..*prev
+    }
+}
+
+/// Solves for the robot motion, repeatedly rejecting the wheel with the
+/// largest residual while the remaining wheels still over-determine it.
+///
+/// `rejected` holds whether each wheel was rejected on the previous update,
+/// so that changes can be logged once.
+fn estimate_motion(
+    wheels: &[WheelGeometry],
+    ds: &[f64],
+    dh: Option<f64>,
+    rejected: &mut [bool],
+) -> Option<MotionEstimate> {
+    let unknowns = if dh.is_some() { 2 } else { 3 };
+    let mut used = vec![true; wheels.len()];
+
+    let estimate = loop {
+        let estimate = solve_motion(wheels, ds, dh, &used)?;
+
+        if used.iter().filter(|used| **used).count() <= unknowns {
+            break estimate;
+        }
+
+        let worst = (0..wheels.len()).filter(|&i| used[i]).max_by(|&a, &b| {
+            estimate.residuals[a]
+                .abs()
+                .total_cmp(&estimate.residuals[b].abs())
+        })?;
+
+        if estimate.residuals[worst].abs() <= MAX_WHEEL_RESIDUAL {
+            break estimate;
+        }
+
+        // Keep the wheel if the others cannot determine the motion without it
+        used[worst] = false;
+        if solve_motion(wheels, ds, dh, &used).is_none() {
+            used[worst] = true;
+            break estimate;
+        }
+    };
+
+    for (i, (rejected, used)) in rejected.iter_mut().zip(used).enumerate() {
+        if *rejected == used {
+            if used {
+                info!("Tracking wheel {i} accepted again");
+            } else {
+                warn!(
+                    "Tracking wheel {i} rejected, residual {:.3}",
+                    estimate.residuals[i]
+                );
+            }
+        }
+        *rejected = !used;
+    }
+
+    Some(estimate)
+}
+
+/// A pair of parallel wheels used as a second heading source.
+struct HeadingWheels {
+    left: TrackingWheel,
+    right: TrackingWheel,
+    estimator: WheelHeading,
+
+    /// Cross-check of the wheel heading against the IMU.
+    check: HeadingCheck,
+}
+
+impl HeadingWheels {
+    fn new(left: TrackingWheel, right: TrackingWheel, track: f64, rotation: Angle) -> Self {
+        let estimator = WheelHeading::new(track, left.distance(), right.distance(), rotation);
+
+        Self {
+            left,
+            right,
+            estimator,
+            check: HeadingCheck::new("wheel", Angle::from_degrees(3.0)),
+        }
+    }
+
+    /// Returns the heading estimated from the wheels.
+    fn rotation(&self) -> Angle {
+        self.estimator
+            .rotation(self.left.distance(), self.right.distance())
+    }
+
+    /// Resets the estimate so that the wheels' current distances correspond
+    /// to `rotation`.
+    fn reset(&mut self, rotation: Angle) {
+        self.estimator
+            .reset(self.left.distance(), self.right.distance(), rotation);
+    }
+}
+
 pub struct Odometry {
     /// Shared robot pose that other subsystems can read.
-    pose: Rc<RefCell<Pose>>,
+    pose: PoseHandle,
 
     // for parking purposes
     pitch: Rc<RefCell<Vec<Angle>>>,
 
+    /// Recent timestamped poses, oldest first.
+    history: Rc<RefCell<VecDeque<(Instant, Pose)>>>,
+
+    /// Timing and filtering parameters, read by the loop every update.
+    params: Rc<Cell<OdometryParameters>>,
+
+    /// Timing statistics of the update loop.
+    stats: Rc<Cell<LoopStats>>,
+
+    /// Acceleration estimated from the filtered velocities.
+    acceleration: Rc<Cell<Acceleration>>,
+
+    /// Second heading source, if one is set.
+    heading_wheels: Rc<RefCell<Option<HeadingWheels>>>,
+
     /// Handle to the background odometry task.
     _task: Task<()>,
 }
@@ -51,95 +353,227 @@ pub struct Odometry {
 impl Odometry {
     /// Creates a new odometry system and starts the background update loop.
     ///
-    /// The loop runs roughly every 10ms (~100 Hz) and continuously updates
-    /// the shared robot pose using the tracking wheels and IMU.
-    pub fn new(
-        pose: Rc<RefCell<Pose>>,
-        mut wheel_1: TrackingWheel,
-        mut wheel_2: TrackingWheel,
-        imu: Imu,
+    /// The loop runs at a fixed rate (every 10ms by default, see
+    /// [`OdometryParameters`]) and continuously updates the shared robot
+    /// pose using the tracking wheels and IMU.
+    ///
+    /// Without an IMU, the wheels must be able to determine the robot's
+    /// rotation on their own (e.g. three wheels).
+    pub fn new(pose: PoseHandle, wheels: Vec<TrackingWheel>, imu: Option<Imu>) -> Self {
+        Self::spawn(pose, wheels, imu, None)
+    }
+
+    /// Creates a new odometry system with a pair of parallel tracking wheels
+    /// `track` apart as a second heading source.
+    ///
+    /// See [`set_heading_wheels`](Self::set_heading_wheels).
+    pub fn with_wheel_heading(
+        pose: PoseHandle,
+        wheels: Vec<TrackingWheel>,
+        imu: Option<Imu>,
+        left: TrackingWheel,
+        right: TrackingWheel,
+        track: f64,
     ) -> Self {
+        Self::spawn(pose, wheels, imu, Some((left, right, track)))
+    }
+
+    fn spawn(
+        pose: PoseHandle,
+        mut wheels: Vec<TrackingWheel>,
+        mut imu: Option<Imu>,
+        heading_wheels: Option<(TrackingWheel, TrackingWheel, f64)>,
+    ) -> Self {
+        // Measurement geometry of each wheel relative to the robot center
+        let geometry: Vec<WheelGeometry> = wheels
+            .iter()
+            .map(|wheel| WheelGeometry::new(wheel.from_center(), wheel.angle()))
+            .collect();
+
+        let all = vec![true; wheels.len()];
+        let zeros = vec![0.0; wheels.len()];
+        assert!(
+            solve_motion(&geometry, &zeros, Some(0.0), &all).is_some(),
+            "Tracking wheels cannot determine robot translation"
+        );
+        assert!(
+            imu.is_some()
+                || heading_wheels.is_some()
+                || solve_motion(&geometry, &zeros, None, &all).is_some(),
+            "Tracking wheels cannot determine robot rotation without an IMU"
+        );
+
         let pitch = Rc::new(RefCell::new(Vec::new()));
+        let history = Rc::new(RefCell::new(VecDeque::with_capacity(HISTORY_LEN)));
+
+        // Keep the recorded poses consistent with corrections made to the
+        // current pose, so later latency-compensated corrections line up
+        pose.subscribe({
+            let history = history.clone();
+            move |correction| {
+                for (_, sample) in history.borrow_mut().iter_mut() {
+                    *sample = correction.after * sample.relative_to(correction.before);
+                }
+            }
+        });
+        let params = Rc::new(Cell::new(OdometryParameters::default()));
+        let stats = Rc::new(Cell::new(LoopStats::default()));
+        let acceleration = Rc::new(Cell::new(Acceleration::default()));
+        let heading_wheels = Rc::new(RefCell::new(heading_wheels.map(|(left, right, track)| {
+            HeadingWheels::new(left, right, track, pose.snapshot().h)
+        })));
 
         Self {
             pose: pose.clone(),
             pitch: pitch.clone(),
+            history: history.clone(),
+            params: params.clone(),
+            stats: stats.clone(),
+            acceleration: acceleration.clone(),
+            heading_wheels: heading_wheels.clone(),
             _task: spawn(async move {
-                let mut prev_time = Instant::now();
-                let mut prev_heading = imu.rotation();
+                let mut rate = FixedRate::new(params.get().period);
+                let mut prev_time = clock::now();
+                let mut prev_heading = imu
+                    .as_mut()
+                    .map(|imu| imu.rotation())
+                    .unwrap_or(pose.snapshot().h);
 
-                // Position of each wheel relative to robot center
-                let offset1 = wheel_1.from_center();
-                let offset2 = wheel_2.from_center();
+                let mut using_wheels = false;
+                let mut holding_heading = false;
 
-                // Wheel orientations relative to robot frame
-                let axis1_angle = wheel_1.angle();
-                let axis2_angle = wheel_2.angle();
-
-                // Determinant used when solving the 2x2 system that reconstructs
-                // robot translation from the two wheel measurements.
-                let det = (axis2_angle - axis1_angle).sin();
-                assert!(det.abs() > 1e-6, "Wheels cannot be parallel");
-
-                // Unit vectors describing each wheel's measurement axis
-                let axis1 = Vec2::new(axis1_angle.cos(), axis1_angle.sin());
-                let axis2 = Vec2::new(axis2_angle.cos(), axis2_angle.sin());
+                let mut rejected = vec![false; wheels.len()];
 
                 loop {
                     // Distance each wheel has moved since the last update
-                    let ds1 = wheel_1.traveled();
-                    let ds2 = wheel_2.traveled();
+                    let ds: Vec<f64> = wheels.iter_mut().map(|wheel| wheel.traveled()).collect();
+
+                    let imu_heading = imu.as_mut().and_then(|imu| {
+                        pitch.replace(imu.pitch());
+                        imu.try_rotation()
+                    });
+
+                    let mut second = heading_wheels.borrow_mut();
+                    let heading = match (imu_heading, second.as_mut()) {
+                        (Some(imu_heading), wheels) => {
+                            if let Some(wheels) = wheels {
+                                if using_wheels {
+                                    info!("IMU recovered, using IMU heading");
+                                    wheels.check.reset();
+                                }
+
+                                let wheel_heading = wheels.rotation();
+                                wheels.check.check(imu_heading, wheel_heading);
+                            }
+                            using_wheels = false;
 
-                    let heading = imu.rotation();
-                    pitch.replace(imu.pitch());
+                            Some(imu_heading)
+                        }
+                        (None, Some(wheels)) => {
+                            // Continue from the last heading instead of jumping to the
+                            // wheel estimate, which may have drifted from the IMU
+                            if !using_wheels {
+                                warn!("IMU unavailable, using wheel heading");
+                                using_wheels = true;
+                                wheels.reset(prev_heading);
+                            }
+
+                            Some(wheels.rotation())
+                        }
+                        // Solve for the rotation from the tracking wheels
+                        (None, None) => None,
+                    };
+                    drop(second);
 
-                    // Change in robot heading since last update
-                    let dh_angle = heading - prev_heading;
-                    let dh = dh_angle.as_radians();
-                    prev_heading = heading;
+                    // Change in robot heading since last update, if known
+                    let dh = heading.map(|heading| (heading - prev_heading).as_radians());
 
-                    // Calculate distance traveled by wheel offsets during rotation
-                    let rot1 = Vec2::new(-dh * offset1.y, dh * offset1.x);
-                    let rot2 = Vec2::new(-dh * offset2.y, dh * offset2.x);
+                    // Solve for the robot-frame motion from the wheel measurements
+                    let mut estimate = estimate_motion(&geometry, &ds, dh, &mut rejected);
 
-                    // Remove the rotational component from the wheel measurements
-                    // leaving only the translation component of the robot motion
-                    let ds1_corr = ds1 - rot1.dot(axis1);
-                    let ds2_corr = ds2 - rot2.dot(axis2);
+                    // Without a heading source the wheels may not determine
+                    // the rotation. Hold the heading and keep tracking the
+                    // translation rather than freezing the pose.
+                    let holding = dh.is_none() && estimate.is_none();
+                    if holding {
+                        estimate = estimate_motion(&geometry, &ds, Some(0.0), &mut rejected);
+                    }
+                    if holding != holding_heading {
+                        if holding {
+                            warn!("No heading source, holding heading");
+                        } else {
+                            info!("Heading source recovered");
+                        }
+                        holding_heading = holding;
+                    }
 
-                    // Calculate the change in position using the inverse of
-                    // the 2x2 matrix formed by the wheel axes.
-                    let delta = Vec2::new(
-                        (axis2.y * ds1_corr - axis1.y * ds2_corr) / det,
-                        (-axis2.x * ds1_corr + axis1.x * ds2_corr) / det,
-                    );
+                    let (delta, dh) = match estimate {
+                        Some(estimate) => (estimate.delta, estimate.dh),
+                        None => (Vec2::new(0.0, 0.0), dh.unwrap_or_default()),
+                    };
+                    prev_heading = heading.unwrap_or(prev_heading + Angle::from_radians(dh));
 
                     // Time since last update
-                    let dt = prev_time.elapsed().as_secs_f64();
-                    prev_time = Instant::now();
-
-                    pose.replace_with(|prev| {
-                        // Use midpoint heading for better integration accuracy
-                        // when converting robot-frame motion into global coordinates
-                        let heading_avg = prev.h + dh_angle / 2.0;
-
-                        // Rotate local robot translation into global field coordinates
-                        let global_delta = delta.rotated(heading_avg.as_radians());
-
-                        Pose {
-                            // Update global position
-                            x: prev.x + global_delta.x,
-                            y: prev.y + global_delta.y,
-                            h: prev.h + dh_angle,
-
-                            // Robot-relative velocities
-                            vf: delta.x / dt, // forward velocity
-                            vs: delta.y / dt, // sideways velocity
-                            omega: dh / dt,   // angular velocity
+                    let params = params.get();
+                    let now = clock::now();
+                    let elapsed = now - prev_time;
+                    prev_time = now;
+
+                    pose.update(|prev| {
+                        let mut next = integrate(prev, delta, dh);
+
+                        // Keep the previous velocities if dt is too small to
+                        // difference over
+                        if elapsed >= params.min_dt {
+                            let dt = elapsed.as_secs_f64();
+
+                            // Filtered robot-relative velocities
+                            next.vf = low_pass(prev.vf, delta.x / dt, dt, params.linear_filter);
+                            next.vs = low_pass(prev.vs, delta.y / dt, dt, params.linear_filter);
+                            next.omega = low_pass(prev.omega, dh / dt, dt, params.angular_filter);
+
+                            // Differentiate the filtered velocities, filtering again
+                            // since differencing amplifies noise
+                            let prev_accel = acceleration.get();
+                            let linear = Vec2::new(next.vf - prev.vf, next.vs - prev.vs) / dt;
+                            acceleration.set(Acceleration {
+                                linear: Vec2::new(
+                                    low_pass(
+                                        prev_accel.linear.x,
+                                        linear.x,
+                                        dt,
+                                        params.linear_filter,
+                                    ),
+                                    low_pass(
+                                        prev_accel.linear.y,
+                                        linear.y,
+                                        dt,
+                                        params.linear_filter,
+                                    ),
+                                ),
+                                angular: low_pass(
+                                    prev_accel.angular,
+                                    (next.omega - prev.omega) / dt,
+                                    dt,
+                                    params.angular_filter,
+                                ),
+                            });
                         }
+
+                        next
                     });
 
-                    sleep(Duration::from_millis(10)).await;
+                    // Record the new pose for latency compensation
+                    let mut history = history.borrow_mut();
+                    if history.len() == HISTORY_LEN {
+                        history.pop_front();
+                    }
+                    history.push_back((prev_time, pose.snapshot()));
+                    drop(history);
+
+                    rate.set_period(params.period);
+                    rate.wait().await;
+                    stats.set(rate.stats());
                 }
             }),
         }
@@ -147,9 +581,60 @@ impl Odometry {
 
     /// Returns the current estimated robot pose.
     ///
-    /// This performs a cheap copy of the Pose stored inside the RefCell.
+    /// This performs a cheap copy of the shared Pose.
     pub fn pose(&self) -> Pose {
-        *self.pose.borrow()
+        self.pose.snapshot()
+    }
+
+    /// Returns a handle to the shared robot pose.
+    pub fn pose_handle(&self) -> PoseHandle {
+        self.pose.clone()
+    }
+
+    /// Returns the current velocity in field coordinates (inches/sec).
+    pub fn field_velocity(&self) -> Vec2<f64> {
+        self.pose().field_velocity()
+    }
+
+    /// Returns the current estimated acceleration.
+    pub fn acceleration(&self) -> Acceleration {
+        self.acceleration.get()
+    }
+
+    /// Predicts the pose `dt` into the future.
+    ///
+    /// Assumes the current acceleration stays constant, so it is only
+    /// accurate over short horizons (a few hundred milliseconds), e.g. for
+    /// feedforward or for starting an action early to account for its delay.
+    pub fn predict(&self, dt: Duration) -> Pose {
+        const STEP: f64 = 0.01;
+
+        let acceleration = self.acceleration();
+        let mut pose = self.pose();
+        let mut remaining = dt.as_secs_f64();
+
+        // Integrate in small steps so the heading change is followed closely
+        while remaining > 0.0 {
+            let step = remaining.min(STEP);
+            remaining -= step;
+
+            let heading_avg = pose.h + Angle::from_radians(pose.omega * step / 2.0);
+            let velocity = Vec2::new(
+                pose.vf + acceleration.linear.x * step / 2.0,
+                pose.vs + acceleration.linear.y * step / 2.0,
+            );
+            let delta = (velocity * step).rotated(heading_avg.as_radians());
+
+            pose.x += delta.x;
+            pose.y += delta.y;
+            pose.h = pose.h
+                + Angle::from_radians((pose.omega + acceleration.angular * step / 2.0) * step);
+            pose.vf += acceleration.linear.x * step;
+            pose.vs += acceleration.linear.y * step;
+            pose.omega += acceleration.angular * step;
+        }
+
+        pose
     }
 
     /// Manually sets the robot pose.
@@ -157,10 +642,273 @@ impl Odometry {
     /// Useful for resetting odometry at the start of autonomous or when
     /// synchronizing with a known field position.
     pub fn set_pose(&mut self, pose: Pose) {
-        *self.pose.borrow_mut() = pose;
+        self.pose.correct("set_pose", pose);
+    }
+
+    /// Sets a pair of parallel wheels `track` apart as a second heading
+    /// source, replacing any previous pair.
+    ///
+    /// The wheel heading is used when every IMU has failed, and is logged as
+    /// a cross-check against the IMU otherwise.
+    pub fn set_heading_wheels(
+        &mut self,
+        left: TrackingWheel,
+        right: TrackingWheel,
+        track: f64,
+    ) -> &mut Self {
+        let wheels = HeadingWheels::new(left, right, track, self.pose().h);
+        self.heading_wheels.replace(Some(wheels));
+        self
+    }
+
+    /// Returns `true` if a second heading source is set.
+    pub fn has_heading_wheels(&self) -> bool {
+        self.heading_wheels.borrow().is_some()
+    }
+
+    /// Returns the timing and filtering parameters of the update loop.
+    pub fn params(&self) -> OdometryParameters {
+        self.params.get()
+    }
+
+    /// Sets the timing and filtering parameters of the update loop.
+    ///
+    /// Takes effect from the next update.
+    pub fn set_params(&mut self, params: OdometryParameters) -> &mut Self {
+        self.params.set(params);
+        self
+    }
+
+    /// Returns the timing statistics of the update loop, e.g. how often it
+    /// missed its deadline.
+    pub fn loop_stats(&self) -> LoopStats {
+        self.stats.get()
+    }
+
+    /// Returns the estimated pose at a past `time`.
+    ///
+    /// Interpolates between the recorded poses around `time`. Times newer
+    /// than the latest update return the current pose. Returns `None` if
+    /// `time` is older than the recorded history.
+    pub fn pose_at(&self, time: Instant) -> Option<Pose> {
+        let history = self.history.borrow();
+
+        let Some(&(latest_time, _)) = history.back() else {
+            return Some(self.pose());
+        };
+        if time >= latest_time {
+            return Some(self.pose());
+        }
+
+        // First sample recorded at or after `time`
+        let next = history.partition_point(|(sample_time, _)| *sample_time < time);
+        if next == 0 {
+            return (history[0].0 == time).then(|| history[0].1);
+        }
+
+        let (t0, p0) = history[next - 1];
+        let (t1, p1) = history[next];
+        let span = (t1 - t0).as_secs_f64();
+        let t = if span > 0.0 {
+            (time - t0).as_secs_f64() / span
+        } else {
+            1.0
+        };
+
+        let lerp = |a: f64, b: f64| a + (b - a) * t;
+
+        Some(Pose {
+            x: lerp(p0.x, p1.x),
+            y: lerp(p0.y, p1.y),
+            h: p0.h + (p1.h - p0.h).wrapped_half() * t,
+            vf: lerp(p0.vf, p1.vf),
+            vs: lerp(p0.vs, p1.vs),
+            omega: lerp(p0.omega, p1.omega),
+        })
+    }
+
+    /// Applies a correction measured at a past `time`.
+    ///
+    /// `corrected` is where the robot actually was at `time`. The motion
+    /// tracked since then is replayed from the corrected pose, so a reading
+    /// that arrives late does not pull the robot back to where it was
+    /// captured. The correction is recorded under `source`.
+    ///
+    /// Returns `false`, without correcting, if `time` is older than the
+    /// recorded history.
+    pub fn correct_at(&mut self, source: &'static str, time: Instant, corrected: Pose) -> bool {
+        let Some(past) = self.pose_at(time) else {
+            return false;
+        };
+
+        let current = self.pose();
+        self.pose
+            .correct(source, corrected * current.relative_to(past));
+
+        true
     }
 
     pub fn pitch(&mut self) -> Angle {
-        (*self.pitch.borrow())[0]
+        self.pitch.borrow().first().copied().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use vexide::runtime::block_on;
+
+    use super::*;
+    use crate::simulation::{DrivetrainParameters, Simulation};
+
+    /// Tracking wheel layouts of the robots, as `(from_center, angle)` in degrees.
+    const LAYOUTS: [(&str, [((f64, f64), f64); 2]); 3] = [
+        (
+            "black",
+            [
+                ((1.61226751, 1.00183612), 45.0),
+                ((1.61226751, -1.00183612), -45.0),
+            ],
+        ),
+        (
+            "champ",
+            [
+                ((1.61226751, -1.00183612), 45.0),
+                ((1.61226751, 1.00183612), -45.0),
+            ],
+        ),
+        (
+            "pink",
+            [
+                ((-1.00288550, -5.93824103), 45.0),
+                ((1.00288550, -5.93824103), -45.0),
+            ],
+        ),
+    ];
+
+    fn geometry(layout: &[((f64, f64), f64); 2]) -> Vec<WheelGeometry> {
+        layout
+            .iter()
+            .map(|&((x, y), angle)| WheelGeometry::new(Vec2::new(x, y), Angle::from_degrees(angle)))
+            .collect()
+    }
+
+    /// Integrates `steps` updates of a constant robot-frame motion `delta`
+    /// and rotation `dh`, measured by the wheels and a perfect IMU.
+    fn drive(
+        wheels: &[WheelGeometry],
+        start: Pose,
+        steps: usize,
+        delta: Vec2<f64>,
+        dh: f64,
+    ) -> Pose {
+        let used = vec![true; wheels.len()];
+        let mut pose = start;
+
+        for _ in 0..steps {
+            // Each wheel moves with the robot, so along a constant twist it
+            // measures exactly its share of the translation and rotation
+            let ds: Vec<f64> = wheels
+                .iter()
+                .map(|wheel| wheel.axis.dot(delta) + dh * wheel.lever)
+                .collect();
+
+            let estimate = solve_motion(wheels, &ds, Some(dh), &used).unwrap();
+            pose = integrate(&pose, estimate.delta, estimate.dh);
+        }
+
+        pose
+    }
+
+    fn assert_pose(name: &str, pose: Pose, x: f64, y: f64, h: f64) {
+        let heading_error = (pose.h - Angle::from_degrees(h))
+            .wrapped_half()
+            .as_degrees();
+
+        assert!(
+            (pose.x - x).abs() < 1e-3 && (pose.y - y).abs() < 1e-3 && heading_error.abs() < 1e-6,
+            "{name}: expected ({x}, {y}, {h}), got ({}, {}, {})",
+            pose.x,
+            pose.y,
+            pose.h.as_degrees()
+        );
+    }
+
+    #[test]
+    fn holds_heading_when_imu_fails() {
+        block_on(async {
+            let start = Pose::new(0.0, 0.0, Angle::ZERO);
+            let sim = Simulation::new(DrivetrainParameters::default(), start);
+            let forward = sim.tracking_wheel(2.0, Vec2::new(0.0, -1.0), Angle::ZERO);
+            let lateral = sim.tracking_wheel(2.0, Vec2::new(-2.0, 0.0), Angle::QUARTER_TURN);
+            let sensor = sim.model().borrow_mut().add_imu();
+            let imu = Imu::new(vec![sensor.clone()], 1.0);
+            let odometry = Odometry::new(PoseHandle::new(start), vec![forward, lateral], Some(imu));
+
+            // Two wheels cannot measure the rotation on their own
+            sensor.update(|state| state.connected = false);
+            let (mut left, mut right) = sim.motor_groups();
+            left.set_voltage(6.0);
+            right.set_voltage(6.0);
+            sim.run(clock::sleep(Duration::from_millis(500))).await;
+
+            let pose = odometry.pose();
+            let actual = sim.pose();
+            assert!(actual.x > 4.0, "robot only drove to x = {}", actual.x);
+            assert!(
+                (pose.x - actual.x).abs() < 0.5,
+                "odometry at x = {}, robot at x = {}",
+                pose.x,
+                actual.x
+            );
+            assert_eq!(pose.h, start.h);
+        });
+    }
+
+    #[test]
+    fn pure_rotation_keeps_position() {
+        for (name, layout) in LAYOUTS {
+            let wheels = geometry(&layout);
+            let start = Pose::new(10.0, 20.0, Angle::ZERO);
+            let pose = drive(&wheels, start, 100, Vec2::new(0.0, 0.0), PI / 200.0);
+
+            assert_pose(name, pose, 10.0, 20.0, 90.0);
+        }
+    }
+
+    #[test]
+    fn pure_translation_follows_heading() {
+        for (name, layout) in LAYOUTS {
+            let wheels = geometry(&layout);
+
+            let start = Pose::new(0.0, 0.0, Angle::from_degrees(30.0));
+            let pose = drive(&wheels, start, 50, Vec2::new(0.5, 0.0), 0.0);
+            assert_pose(name, pose, 21.650635, 12.5, 30.0);
+
+            let start = Pose::new(0.0, 0.0, Angle::ZERO);
+            let pose = drive(&wheels, start, 50, Vec2::new(0.0, 0.2), 0.0);
+            assert_pose(name, pose, 0.0, 10.0, 0.0);
+        }
+    }
+
+    #[test]
+    fn arcs_end_on_circle() {
+        const RADIUS: f64 = 24.0;
+        const STEPS: usize = 200;
+
+        for (name, layout) in LAYOUTS {
+            let wheels = geometry(&layout);
+            let start = Pose::new(0.0, 0.0, Angle::ZERO);
+            let dh = PI / 2.0 / STEPS as f64;
+
+            // Quarter circle to the left
+            let pose = drive(&wheels, start, STEPS, Vec2::new(RADIUS * dh, 0.0), dh);
+            assert_pose(name, pose, RADIUS, RADIUS, 90.0);
+
+            // Quarter circle to the right
+            let pose = drive(&wheels, start, STEPS, Vec2::new(RADIUS * dh, 0.0), -dh);
+            assert_pose(name, pose, RADIUS, -RADIUS, -90.0);
+        }
     }
 }
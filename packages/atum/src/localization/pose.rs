@@ -1,4 +1,4 @@
-use std::fmt::Display;
+use std::{fmt::Display, ops::Mul};
 
 use vexide::math::Angle;
 
@@ -16,7 +16,7 @@ use crate::localization::vec2::Vec2;
 /// - +y = left
 ///
 /// Velocities are expressed in the robot frame.
-#[derive(Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default)]
 pub struct Pose {
     /// Global x position on the field
     pub x: f64,
@@ -54,6 +54,86 @@ impl Pose {
     pub fn position(&self) -> Vec2<f64> {
         Vec2::new(self.x, self.y)
     }
+
+    /// Returns the velocity in field coordinates (inches/sec).
+    pub fn field_velocity(&self) -> Vec2<f64> {
+        Vec2::new(self.vf, self.vs).rotated(self.h.as_radians())
+    }
+
+    /// Returns the inverse transform, which maps this pose back to the origin.
+    ///
+    /// `pose * pose.inverse()` is the identity pose.
+    pub fn inverse(&self) -> Self {
+        let position = self.position().rotated(-self.h.as_radians());
+        Self::new(-position.x, -position.y, -self.h)
+    }
+
+    /// Returns this pose expressed in the frame of `origin`.
+    ///
+    /// e.g. `target.relative_to(robot)` is where the target is from the
+    /// robot's point of view (+x forward, +y left).
+    pub fn relative_to(&self, origin: Pose) -> Self {
+        origin.inverse() * *self
+    }
+
+    /// Converts a point in this pose's (robot) frame to field coordinates.
+    pub fn to_field(&self, point: Vec2<f64>) -> Vec2<f64> {
+        self.position() + point.rotated(self.h.as_radians())
+    }
+
+    /// Converts a point in field coordinates to this pose's (robot) frame.
+    pub fn to_robot(&self, point: Vec2<f64>) -> Vec2<f64> {
+        (point - self.position()).rotated(-self.h.as_radians())
+    }
+
+    /// Returns the straight-line distance to a field point.
+    pub fn distance_to(&self, point: Vec2<f64>) -> f64 {
+        self.position().distance_to(point)
+    }
+
+    /// Returns the field-frame direction from this pose to a point.
+    pub fn angle_to(&self, point: Vec2<f64>) -> Angle {
+        Angle::from_radians((point - self.position()).angle())
+    }
+
+    /// Returns how far the robot must turn to face a point, in `[-180°, 180°)`.
+    pub fn bearing_to(&self, point: Vec2<f64>) -> Angle {
+        (self.angle_to(point) - self.h).wrapped_half()
+    }
+
+    /// Returns the pose mirrored to the other alliance's side of a square
+    /// field of `field_size`, i.e. rotated 180° about the field center.
+    ///
+    /// Velocities are robot-relative and stay unchanged.
+    pub fn mirrored(&self, field_size: f64) -> Self {
+        let position = self.position().mirrored(field_size);
+
+        Self {
+            x: position.x,
+            y: position.y,
+            h: (self.h + Angle::HALF_TURN).wrapped_full(),
+            ..*self
+        }
+    }
+}
+
+impl Mul for Pose {
+    type Output = Self;
+
+    /// Composes two poses, treating `rhs` as expressed in the frame of `self`.
+    ///
+    /// e.g. `robot * offset` is where a point mounted at `offset` on the robot
+    /// is on the field. Velocities are taken from `rhs`.
+    fn mul(self, rhs: Pose) -> Self {
+        let position = self.to_field(rhs.position());
+
+        Self {
+            x: position.x,
+            y: position.y,
+            h: self.h + rhs.h,
+            ..rhs
+        }
+    }
 }
 
 impl Display for Pose {
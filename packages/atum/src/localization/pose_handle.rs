@@ -0,0 +1,148 @@
+//! Shared Pose
+//!
+//! The robot pose is written by the odometry task, corrected by localization
+//! (e.g. raycasting against the field walls) and read by motion commands and
+//! autonomous routines. [`PoseHandle`] is a cheaply cloneable handle to that
+//! shared pose.
+//!
+//! Every access borrows the pose only for the duration of the call, so no
+//! borrow is ever held across an `.await` and overlapping access from
+//! different tasks cannot panic.
+//!
+//! Writes are split into two kinds:
+//! - updates, made every loop by odometry as the robot moves
+//! - corrections, which move the pose to a new estimate and are recorded
+//!   with their source and magnitude, then passed to every subscriber
+
+use std::{cell::RefCell, collections::VecDeque, rc::Rc, time::Instant};
+
+use log::debug;
+use vexide::math::Angle;
+
+use super::pose::Pose;
+use crate::clock;
+
+/// Number of recent corrections kept for diagnostics.
+const CORRECTION_HISTORY_LEN: usize = 32;
+
+/// A recorded change of the pose made by something other than odometry.
+#[derive(Debug, Clone, Copy)]
+pub struct Correction {
+    /// Who made the correction, e.g. `"rcl"`.
+    pub source: &'static str,
+
+    /// When the correction was made.
+    pub time: Instant,
+
+    /// Pose before the correction.
+    pub before: Pose,
+
+    /// Pose after the correction.
+    pub after: Pose,
+}
+
+impl Correction {
+    /// Returns how far the position moved.
+    pub fn distance(&self) -> f64 {
+        self.before.position().distance_to(self.after.position())
+    }
+
+    /// Returns how far the heading turned, in `[-180°, 180°)`.
+    pub fn rotation(&self) -> Angle {
+        (self.after.h - self.before.h).wrapped_half()
+    }
+}
+
+type Subscriber = Box<dyn FnMut(&Correction)>;
+
+struct Shared {
+    pose: RefCell<Pose>,
+    corrections: RefCell<VecDeque<Correction>>,
+    subscribers: RefCell<Vec<Subscriber>>,
+}
+
+/// Shared handle to the robot pose.
+///
+/// Clones refer to the same pose.
+#[derive(Clone)]
+pub struct PoseHandle {
+    shared: Rc<Shared>,
+}
+
+impl PoseHandle {
+    /// Creates a handle to a new shared pose.
+    pub fn new(pose: Pose) -> Self {
+        Self {
+            shared: Rc::new(Shared {
+                pose: RefCell::new(pose),
+                corrections: RefCell::new(VecDeque::with_capacity(CORRECTION_HISTORY_LEN)),
+                subscribers: RefCell::new(Vec::new()),
+            }),
+        }
+    }
+
+    /// Returns a copy of the current pose.
+    pub fn snapshot(&self) -> Pose {
+        *self.shared.pose.borrow()
+    }
+
+    /// Reads the current pose without copying it.
+    pub fn read<R>(&self, f: impl FnOnce(&Pose) -> R) -> R {
+        f(&self.shared.pose.borrow())
+    }
+
+    /// Updates the pose from odometry.
+    ///
+    /// Updates are not recorded as corrections and do not notify subscribers.
+    pub fn update(&self, f: impl FnOnce(&Pose) -> Pose) {
+        let mut pose = self.shared.pose.borrow_mut();
+        *pose = f(&pose);
+    }
+
+    /// Moves the pose to a new estimate, recording `source` and how far the
+    /// pose moved, then notifies every subscriber.
+    pub fn correct(&self, source: &'static str, pose: Pose) -> Correction {
+        let before = self.shared.pose.replace(pose);
+        let correction = Correction {
+            source,
+            time: clock::now(),
+            before,
+            after: pose,
+        };
+
+        debug!(
+            "{source} corrected pose by {:.2} in, {:.2} deg",
+            correction.distance(),
+            correction.rotation().as_degrees()
+        );
+
+        let mut corrections = self.shared.corrections.borrow_mut();
+        if corrections.len() == CORRECTION_HISTORY_LEN {
+            corrections.pop_front();
+        }
+        corrections.push_back(correction);
+        drop(corrections);
+
+        // Take the subscribers out while notifying them so they are free to
+        // read, correct or subscribe to this handle
+        let mut subscribers = self.shared.subscribers.take();
+        for subscriber in &mut subscribers {
+            subscriber(&correction);
+        }
+        let mut added = self.shared.subscribers.borrow_mut();
+        subscribers.append(&mut added);
+        *added = subscribers;
+
+        correction
+    }
+
+    /// Returns the most recent corrections, oldest first.
+    pub fn corrections(&self) -> Vec<Correction> {
+        self.shared.corrections.borrow().iter().copied().collect()
+    }
+
+    /// Calls `f` with every future correction.
+    pub fn subscribe(&self, f: impl FnMut(&Correction) + 'static) {
+        self.shared.subscribers.borrow_mut().push(Box::new(f));
+    }
+}
@@ -1,15 +1,66 @@
+use std::fmt::Display;
+
 use log::debug;
 
-use super::{pose::Pose, vec2::Vec2};
+use super::{
+    pose::Pose,
+    pose_handle::{Correction, PoseHandle},
+};
 use crate::{
     hardware::wall_distance_sensor::{Wall, WallDistanceSensor},
     localization::shape::{Circle, Shape},
 };
 
-const FIELD_SIZE: f64 = 140.42;
+/// Default distance between opposing field walls.
+pub const FIELD_SIZE: f64 = 140.42;
+
+/// Default maximum disagreement allowed between a reading and the current pose.
 pub const MAX_ERROR: f64 = 13.0;
-const MAX_RAYCAST_DIST: f64 = FIELD_SIZE * 2.0;
-const MIN_AXIS_COMPONENT: f64 = 0.8;
+
+/// Default maximum length of a predicted sensor ray.
+pub const MAX_RAYCAST_DIST: f64 = FIELD_SIZE * 2.0;
+
+/// Default minimum component of the beam direction along the axis it corrects.
+pub const MIN_AXIS_COMPONENT: f64 = 0.8;
+
+/// Tuning parameters for raycast localization.
+///
+/// The defaults describe a standard field and the gates used on every robot,
+/// so most setups only need to override `max_error`.
+#[derive(Debug, Clone, Copy)]
+pub struct RclParameters {
+    /// Distance between opposing field walls.
+    pub field_size: f64,
+
+    /// Maximum length of a predicted sensor ray.
+    pub max_raycast_dist: f64,
+
+    /// Minimum component of the beam direction along the corrected axis.
+    /// Rejects shallow hits where a small heading error causes a large
+    /// position error.
+    pub min_axis_component: f64,
+
+    /// Maximum allowed residual between measured and predicted distance, and
+    /// maximum allowed jump between the current pose and the corrected pose.
+    pub max_error: f64,
+}
+
+impl Default for RclParameters {
+    fn default() -> Self {
+        Self {
+            field_size: FIELD_SIZE,
+            max_raycast_dist: MAX_RAYCAST_DIST,
+            min_axis_component: MIN_AXIS_COMPONENT,
+            max_error: MAX_ERROR,
+        }
+    }
+}
+
+impl RclParameters {
+    fn in_field(&self, value: f64) -> bool {
+        (0.0..=self.field_size).contains(&value)
+    }
+}
 
 #[derive(Debug, Clone, Copy, Default)]
 pub struct PoseCorrection {
@@ -21,94 +72,188 @@ impl PoseCorrection {
     pub fn is_empty(&self) -> bool {
         self.x.is_none() && self.y.is_none()
     }
+
+    /// Returns `pose` with the corrected coordinates replaced.
+    pub fn applied_to(&self, pose: Pose) -> Pose {
+        Pose {
+            x: self.x.unwrap_or(pose.x),
+            y: self.y.unwrap_or(pose.y),
+            ..pose
+        }
+    }
+}
+
+/// Why a sensor reading was or was not used for a correction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SensorVerdict {
+    /// The reading was used to correct the pose.
+    Accepted,
+
+    /// The sensor returned an error.
+    ReadError,
+
+    /// No object was detected, or it was out of range or the wrong size.
+    NoReading,
+
+    /// The beam does not hit a wall from the current pose.
+    NoWallHit,
+
+    /// The measured distance disagrees too much with the predicted distance.
+    ResidualTooLarge,
+
+    /// The beam passes through a known field object.
+    Occluded,
+
+    /// The beam hits the wall at too shallow an angle.
+    ShallowAngle,
+
+    /// The corrected position lies outside of the field.
+    OutOfField,
+
+    /// The corrected position is too far from the current pose.
+    PoseJump,
+}
+
+/// Per-sensor result of a raycast localization update.
+#[derive(Debug, Clone, Copy)]
+pub struct SensorDiagnostic {
+    /// Index of the sensor in the order it was given to [`RaycastLocalization::new`].
+    pub index: usize,
+
+    /// Measured distance in inches.
+    pub measured: Option<f64>,
+
+    /// Wall the beam is predicted to hit.
+    pub wall: Option<Wall>,
+
+    /// Predicted distance to the wall in inches.
+    pub predicted: Option<f64>,
+
+    /// Measured minus predicted distance.
+    pub residual: Option<f64>,
+
+    /// Robot x (left/right walls) or y (bottom/top walls) implied by the reading.
+    pub estimate: Option<f64>,
+
+    /// Whether the reading was accepted, and if not, why.
+    pub verdict: SensorVerdict,
+}
+
+impl SensorDiagnostic {
+    fn new(index: usize) -> Self {
+        Self {
+            index,
+            measured: None,
+            wall: None,
+            predicted: None,
+            residual: None,
+            estimate: None,
+            verdict: SensorVerdict::NoReading,
+        }
+    }
+
+    fn verdict(mut self, verdict: SensorVerdict) -> Self {
+        self.verdict = verdict;
+        self
+    }
+}
+
+impl Display for SensorDiagnostic {
+    /// Formats the diagnostic for logging.
+    ///
+    /// Output format:
+    ///
+    /// `sensor i: verdict wall measured predicted residual estimate`
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "sensor {}: {:?}", self.index, self.verdict)?;
+
+        if let Some(wall) = self.wall {
+            write!(f, " {:?}", wall)?;
+        }
+        if let Some(measured) = self.measured {
+            write!(f, " measured {:.2}", measured)?;
+        }
+        if let Some(predicted) = self.predicted {
+            write!(f, " predicted {:.2}", predicted)?;
+        }
+        if let Some(residual) = self.residual {
+            write!(f, " residual {:.2}", residual)?;
+        }
+        if let Some(estimate) = self.estimate {
+            write!(f, " estimate {:.2}", estimate)?;
+        }
+
+        Ok(())
+    }
 }
 
 pub struct RaycastLocalization {
     sensors: Vec<WallDistanceSensor>,
     objects: Vec<Circle>,
+    params: RclParameters,
 }
 
 impl RaycastLocalization {
-    pub fn new(sensors: Vec<WallDistanceSensor>, objects: Vec<Circle>) -> Self {
-        Self { sensors, objects }
+    pub fn new(
+        sensors: Vec<WallDistanceSensor>,
+        objects: Vec<Circle>,
+        params: RclParameters,
+    ) -> Self {
+        Self {
+            sensors,
+            objects,
+            params,
+        }
     }
 
-    pub fn correction(&self, pose: Pose, max_error: f64) -> Option<PoseCorrection> {
-        let robot_position = pose.position();
+    /// Returns the parameters used for raycasting and gating.
+    pub fn params(&self) -> RclParameters {
+        self.params
+    }
 
-        let mut xs = Vec::new();
-        let mut ys = Vec::new();
+    /// Returns the distance sensors used for localization.
+    pub fn sensors(&self) -> &[WallDistanceSensor] {
+        &self.sensors
+    }
 
-        'outer: for sensor in &self.sensors {
-            let measured = match sensor.distance() {
-                Ok(Some(d)) => d,
-                _ => continue,
-            };
+    /// Sets the maximum error allowed for a reading to be accepted.
+    pub fn set_max_error(&mut self, max_error: f64) -> &mut Self {
+        self.params.max_error = max_error;
+        self
+    }
 
-            let hit =
-                match sensor.predicted_hit(robot_position, pose.h, FIELD_SIZE, MAX_RAYCAST_DIST) {
-                    Some(hit) => hit,
-                    None => continue,
-                };
+    /// Evaluates every sensor against the given pose.
+    ///
+    /// Returns one entry per sensor describing the reading, the predicted
+    /// wall, the residual and whether the reading would be accepted.
+    pub fn diagnostics(&self, pose: Pose) -> Vec<SensorDiagnostic> {
+        self.sensors
+            .iter()
+            .enumerate()
+            .map(|(index, sensor)| self.evaluate(index, sensor, pose))
+            .collect()
+    }
 
-            if (measured - hit.distance).abs() > max_error {
-                continue;
-            }
+    /// Logs the diagnostics of every sensor at debug level.
+    pub fn log_diagnostics(&self, pose: Pose) {
+        for diagnostic in self.diagnostics(pose) {
+            debug!("{}", diagnostic);
+        }
+    }
 
-            let world_position = sensor.world_position(pose.position(), pose.h);
-            let world_angle = sensor.world_angle(pose.h);
-            let theta = world_angle.as_radians();
-            let dx = theta.cos();
-            let dy = theta.sin();
-            
-            for object in self.objects.iter() {
-                if object.is_intersecting(world_position, world_angle, hit.distance) {
-                    continue 'outer;
-                }
-            }
+    pub fn correction(&self, pose: Pose) -> Option<PoseCorrection> {
+        let mut xs = Vec::new();
+        let mut ys = Vec::new();
 
-            match hit.wall {
-                Wall::Left | Wall::Right if dx.abs() < MIN_AXIS_COMPONENT => continue,
-                Wall::Bottom | Wall::Top if dy.abs() < MIN_AXIS_COMPONENT => continue,
-                _ => {}
+        for diagnostic in self.diagnostics(pose) {
+            if diagnostic.verdict != SensorVerdict::Accepted {
+                continue;
             }
 
-            // rotated sensor offset in world frame
-            let rotated_offset = sensor.world_position(Vec2::new(0.0, 0.0), pose.h);
-
-            match hit.wall {
-                Wall::Left => {
-                    let sensor_x = -dx * measured;
-                    let robot_x = sensor_x - rotated_offset.x;
-
-                    if in_field(robot_x) && (robot_x - pose.x).abs() <= max_error {
-                        xs.push(robot_x);
-                    }
-                }
-                Wall::Right => {
-                    let sensor_x = FIELD_SIZE - dx * measured;
-                    let robot_x = sensor_x - rotated_offset.x;
-
-                    if in_field(robot_x) && (robot_x - pose.x).abs() <= max_error {
-                        xs.push(robot_x);
-                    }
-                }
-                Wall::Bottom => {
-                    let sensor_y = -dy * measured;
-                    let robot_y = sensor_y - rotated_offset.y;
-
-                    if in_field(robot_y) && (robot_y - pose.y).abs() <= max_error {
-                        ys.push(robot_y);
-                    }
-                }
-                Wall::Top => {
-                    let sensor_y = FIELD_SIZE - dy * measured;
-                    let robot_y = sensor_y - rotated_offset.y;
-
-                    if in_field(robot_y) && (robot_y - pose.y).abs() <= max_error {
-                        ys.push(robot_y);
-                    }
-                }
+            match (diagnostic.wall, diagnostic.estimate) {
+                (Some(Wall::Left | Wall::Right), Some(x)) => xs.push(x),
+                (Some(Wall::Bottom | Wall::Top), Some(y)) => ys.push(y),
+                _ => {}
             }
         }
 
@@ -124,20 +269,90 @@ impl RaycastLocalization {
         }
     }
 
-    pub fn corrected_pose(&self, pose: Pose, max_error: f64) -> Pose {
-        let mut corrected = pose;
+    pub fn corrected_pose(&self, pose: Pose) -> Pose {
+        match self.correction(pose) {
+            Some(correction) => correction.applied_to(pose),
+            None => pose,
+        }
+    }
 
-        if let Some(correction) = self.correction(pose, max_error) {
-            if let Some(x) = correction.x {
-                corrected.x = x;
-            }
+    /// Corrects the shared pose from the current sensor readings.
+    ///
+    /// Returns the recorded correction, or `None` if no reading was accepted.
+    pub fn apply(&self, pose: &PoseHandle) -> Option<Correction> {
+        let current = pose.snapshot();
+        let correction = self.correction(current)?;
+
+        Some(pose.correct("rcl", correction.applied_to(current)))
+    }
+
+    fn evaluate(&self, index: usize, sensor: &WallDistanceSensor, pose: Pose) -> SensorDiagnostic {
+        let params = &self.params;
+        let mut diagnostic = SensorDiagnostic::new(index);
+
+        let measured = match sensor.distance() {
+            Ok(Some(d)) => d,
+            Ok(None) => return diagnostic.verdict(SensorVerdict::NoReading),
+            Err(_) => return diagnostic.verdict(SensorVerdict::ReadError),
+        };
+        diagnostic.measured = Some(measured);
+
+        let hit = match sensor.predicted_hit(pose, params.field_size, params.max_raycast_dist) {
+            Some(hit) => hit,
+            None => return diagnostic.verdict(SensorVerdict::NoWallHit),
+        };
+        diagnostic.wall = Some(hit.wall);
+        diagnostic.predicted = Some(hit.distance);
+        diagnostic.residual = Some(measured - hit.distance);
+
+        if (measured - hit.distance).abs() > params.max_error {
+            return diagnostic.verdict(SensorVerdict::ResidualTooLarge);
+        }
 
-            if let Some(y) = correction.y {
-                corrected.y = y;
+        let world_position = sensor.world_position(pose);
+        let world_angle = sensor.world_angle(pose.h);
+        let theta = world_angle.as_radians();
+        let dx = theta.cos();
+        let dy = theta.sin();
+
+        if self
+            .objects
+            .iter()
+            .any(|object| object.is_intersecting(world_position, world_angle, hit.distance))
+        {
+            return diagnostic.verdict(SensorVerdict::Occluded);
+        }
+
+        match hit.wall {
+            Wall::Left | Wall::Right if dx.abs() < params.min_axis_component => {
+                return diagnostic.verdict(SensorVerdict::ShallowAngle);
+            }
+            Wall::Bottom | Wall::Top if dy.abs() < params.min_axis_component => {
+                return diagnostic.verdict(SensorVerdict::ShallowAngle);
             }
+            _ => {}
+        }
+
+        // rotated sensor offset in world frame
+        let rotated_offset = world_position - pose.position();
+
+        let (estimate, current) = match hit.wall {
+            Wall::Left => (-dx * measured - rotated_offset.x, pose.x),
+            Wall::Right => (params.field_size - dx * measured - rotated_offset.x, pose.x),
+            Wall::Bottom => (-dy * measured - rotated_offset.y, pose.y),
+            Wall::Top => (params.field_size - dy * measured - rotated_offset.y, pose.y),
+        };
+        diagnostic.estimate = Some(estimate);
+
+        if !params.in_field(estimate) {
+            return diagnostic.verdict(SensorVerdict::OutOfField);
+        }
+
+        if (estimate - current).abs() > params.max_error {
+            return diagnostic.verdict(SensorVerdict::PoseJump);
         }
 
-        corrected
+        diagnostic.verdict(SensorVerdict::Accepted)
     }
 }
 
@@ -149,6 +364,157 @@ fn mean(values: &[f64]) -> Option<f64> {
     }
 }
 
-fn in_field(value: f64) -> bool {
-    (0.0..=FIELD_SIZE).contains(&value)
+#[cfg(test)]
+mod tests {
+    use vexide::math::Angle;
+
+    use super::*;
+    use crate::{hardware::mock::MockDistanceSensor, localization::vec2::Vec2};
+
+    const PARAMS: RclParameters = RclParameters {
+        field_size: 144.0,
+        max_raycast_dist: 300.0,
+        min_axis_component: MIN_AXIS_COMPONENT,
+        max_error: 5.0,
+    };
+
+    /// Robot pose the readings are evaluated against.
+    const POSE: Pose = Pose {
+        x: 72.0,
+        y: 36.0,
+        h: Angle::ZERO,
+        vf: 0.0,
+        vs: 0.0,
+        omega: 0.0,
+    };
+
+    /// Creates a localization with a sensor at the robot center for each of
+    /// the given beam angles, returning the mocks to set their readings.
+    fn localization(
+        degrees: &[f64],
+        objects: Vec<Circle>,
+        params: RclParameters,
+    ) -> (RaycastLocalization, Vec<MockDistanceSensor>) {
+        let mocks: Vec<MockDistanceSensor> =
+            degrees.iter().map(|_| MockDistanceSensor::new()).collect();
+        let sensors = degrees
+            .iter()
+            .zip(&mocks)
+            .map(|(&degrees, mock)| {
+                WallDistanceSensor::with_device(
+                    mock.clone(),
+                    Vec2::new(0.0, 0.0),
+                    Angle::from_degrees(degrees),
+                    0..400,
+                )
+            })
+            .collect();
+
+        (RaycastLocalization::new(sensors, objects, params), mocks)
+    }
+
+    fn verdicts(rcl: &RaycastLocalization) -> Vec<SensorVerdict> {
+        rcl.diagnostics(POSE)
+            .iter()
+            .map(|diagnostic| diagnostic.verdict)
+            .collect()
+    }
+
+    fn assert_close(actual: Option<f64>, expected: f64) {
+        let actual = actual.expect("expected a correction");
+        assert!(
+            (actual - expected).abs() < 0.01,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn accepts_consistent_reading() {
+        let (rcl, mocks) = localization(&[-90.0], Vec::new(), PARAMS);
+        mocks[0].set_distance(37.0);
+
+        assert_eq!(verdicts(&rcl), [SensorVerdict::Accepted]);
+
+        let correction = rcl.correction(POSE).unwrap();
+        assert_close(correction.y, 37.0);
+        assert!(correction.x.is_none());
+    }
+
+    #[test]
+    fn averages_readings_on_same_axis() {
+        let (rcl, mocks) = localization(&[0.0, 180.0], Vec::new(), PARAMS);
+        mocks[0].set_distance(71.0);
+        mocks[1].set_distance(74.0);
+
+        let correction = rcl.correction(POSE).unwrap();
+        assert_close(correction.x, 73.5);
+        assert!(correction.y.is_none());
+    }
+
+    #[test]
+    fn rejects_missing_reading() {
+        let (rcl, mocks) = localization(&[-90.0], Vec::new(), PARAMS);
+        mocks[0].set_reading(None);
+
+        assert_eq!(verdicts(&rcl), [SensorVerdict::NoReading]);
+        assert!(rcl.correction(POSE).is_none());
+    }
+
+    #[test]
+    fn rejects_large_residual() {
+        let (rcl, mocks) = localization(&[-90.0], Vec::new(), PARAMS);
+        mocks[0].set_distance(42.0);
+
+        assert_eq!(verdicts(&rcl), [SensorVerdict::ResidualTooLarge]);
+        assert!(rcl.correction(POSE).is_none());
+    }
+
+    #[test]
+    fn rejects_occluded_beam() {
+        let objects = vec![Circle::new(Vec2::new(72.0, 20.0), 3.0)];
+        let (rcl, mocks) = localization(&[-90.0], objects, PARAMS);
+        mocks[0].set_distance(36.0);
+
+        assert_eq!(verdicts(&rcl), [SensorVerdict::Occluded]);
+        assert!(rcl.correction(POSE).is_none());
+    }
+
+    #[test]
+    fn rejects_shallow_angle() {
+        // Hits the bottom wall 72" away with only half of the beam along y
+        let (rcl, mocks) = localization(&[-30.0], Vec::new(), PARAMS);
+        mocks[0].set_distance(72.0);
+
+        assert_eq!(verdicts(&rcl), [SensorVerdict::ShallowAngle]);
+        assert!(rcl.correction(POSE).is_none());
+    }
+
+    #[test]
+    fn rejects_beam_without_wall() {
+        let params = RclParameters {
+            max_raycast_dist: 10.0,
+            ..PARAMS
+        };
+        let (rcl, mocks) = localization(&[-90.0], Vec::new(), params);
+        mocks[0].set_distance(36.0);
+
+        assert_eq!(verdicts(&rcl), [SensorVerdict::NoWallHit]);
+        assert!(rcl.correction(POSE).is_none());
+    }
+
+    #[test]
+    fn gates_each_sensor_independently() {
+        let (rcl, mocks) = localization(&[-90.0, 0.0], Vec::new(), PARAMS);
+        mocks[0].set_distance(35.0);
+        mocks[1].set_distance(60.0);
+
+        assert_eq!(
+            verdicts(&rcl),
+            [SensorVerdict::Accepted, SensorVerdict::ResidualTooLarge]
+        );
+
+        let correction = rcl.correction(POSE).unwrap();
+        assert_close(correction.y, 35.0);
+        assert!(correction.x.is_none());
+    }
 }
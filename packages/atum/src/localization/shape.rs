@@ -26,29 +26,55 @@ impl Circle {
 
 impl Shape for Circle {
     fn is_intersecting(&self, spos: Vec2<f64>, sh: Angle, dist: f64) -> bool {
-        let dx = sh.cos();
-        let dy = sh.sin();
+        let direction = Vec2::from_polar(1.0, sh.as_radians());
 
-        // Vector from ray start to circle center
-        let cx = self.center.x - spos.x;
-        let cy = self.center.y - spos.y;
+        // Projection of the circle center onto the ray, clamped to the
+        // finite sensor range
+        let t = (self.center - spos).dot(direction).clamp(0.0, dist);
 
-        // Projection onto ray direction
-        let mut t = cx * dx + cy * dy;
+        // Closest point on the ray segment
+        let closest = spos + t * direction;
+
+        closest.distance_to(self.center) <= self.radius
+    }
+}
 
-        // Clamp to finite sensor range
-        t = t.clamp(0.0, dist);
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        // Closest point on the ray segment
-        let closest_x = spos.x + t * dx;
-        let closest_y = spos.y + t * dy;
+    fn intersects(center: (f64, f64), radius: f64, degrees: f64, dist: f64) -> bool {
+        Circle::new(Vec2::new(center.0, center.1), radius).is_intersecting(
+            Vec2::new(0.0, 0.0),
+            Angle::from_degrees(degrees),
+            dist,
+        )
+    }
 
-        // Distance from closest point to circle center
-        let dist_x = self.center.x - closest_x;
-        let dist_y = self.center.y - closest_y;
+    #[test]
+    fn ray_through_circle() {
+        assert!(intersects((10.0, 0.0), 1.0, 0.0, 20.0));
+        assert!(intersects((10.0, 10.0), 0.5, 45.0, 20.0));
+    }
+
+    #[test]
+    fn ray_tangent_to_circle() {
+        assert!(intersects((10.0, 1.0), 1.0, 0.0, 20.0));
+        assert!(!intersects((10.0, 1.01), 1.0, 0.0, 20.0));
+    }
+
+    #[test]
+    fn ray_limited_by_range() {
+        // The segment ends before the circle, touching its edge at 9"
+        assert!(!intersects((10.0, 0.0), 1.0, 0.0, 8.0));
+        assert!(intersects((10.0, 0.0), 1.0, 0.0, 9.0));
+    }
 
-        let dist_sq = dist_x * dist_x + dist_y * dist_y;
+    #[test]
+    fn circle_behind_ray() {
+        assert!(!intersects((10.0, 0.0), 1.0, 180.0, 20.0));
 
-        dist_sq <= self.radius * self.radius
+        // Starting inside the circle always intersects
+        assert!(intersects((0.5, 0.0), 1.0, 180.0, 20.0));
     }
 }
@@ -1,4 +1,4 @@
-use std::ops::{Add, Mul, Sub};
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
 
 use num_traits::Float;
 
@@ -19,6 +19,14 @@ impl<T> Vec2<T> {
     }
 }
 
+impl Vec2<f64> {
+    /// Returns the point mirrored to the other alliance's side of a square
+    /// field of `field_size`, i.e. rotated 180° about the field center.
+    pub const fn mirrored(self, field_size: f64) -> Self {
+        Self::new(field_size - self.x, field_size - self.y)
+    }
+}
+
 impl<T: Copy + Float> Vec2<T> {
     /// Creates a vector from polar coordinates.
     ///
@@ -40,6 +48,74 @@ impl<T: Copy + Float> Vec2<T> {
     pub fn length(&self) -> T {
         self.x.hypot(self.y)
     }
+
+    /// Returns a unit vector in the same direction.
+    ///
+    /// A zero vector has no direction and is returned unchanged.
+    pub fn normalized(&self) -> Self {
+        let length = self.length();
+
+        if length.is_zero() {
+            *self
+        } else {
+            Self {
+                x: self.x / length,
+                y: self.y / length,
+            }
+        }
+    }
+
+    /// Returns the distance between two points.
+    pub fn distance_to(&self, other: Vec2<T>) -> T {
+        (other.x - self.x).hypot(other.y - self.y)
+    }
+
+    /// Linearly interpolates towards `other`.
+    ///
+    /// `t = 0` returns `self`, `t = 1` returns `other`.
+    pub fn lerp(&self, other: Vec2<T>, t: T) -> Self {
+        Self {
+            x: self.x + (other.x - self.x) * t,
+            y: self.y + (other.y - self.y) * t,
+        }
+    }
+
+    /// Returns the projection of this vector onto `other`.
+    ///
+    /// Projecting onto a zero vector returns a zero vector.
+    pub fn projected_onto(&self, other: Vec2<T>) -> Self {
+        let length_sq = other.x * other.x + other.y * other.y;
+
+        if length_sq.is_zero() {
+            return Self {
+                x: T::zero(),
+                y: T::zero(),
+            };
+        }
+
+        let scale = (self.x * other.x + self.y * other.y) / length_sq;
+        Self {
+            x: other.x * scale,
+            y: other.y * scale,
+        }
+    }
+
+    /// Returns the vector rotated 90° counterclockwise.
+    pub fn perpendicular(&self) -> Self {
+        Self {
+            x: -self.y,
+            y: self.x,
+        }
+    }
+
+    /// Returns the signed angle (radians) to rotate this vector onto `other`,
+    /// in `[-π, π]`. Positive is counterclockwise.
+    pub fn angle_between(&self, other: Vec2<T>) -> T {
+        let cross = self.x * other.y - self.y * other.x;
+        let dot = self.x * other.x + self.y * other.y;
+
+        cross.atan2(dot)
+    }
 }
 
 impl<T: Float + Copy + Mul<Output = T> + Sub<Output = T>> Vec2<T> {
@@ -140,3 +216,60 @@ impl<T: Mul<Output = T> + Copy> Mul<T> for Vec2<T> {
     }
 }
 
+impl<T: Div<Output = T> + Copy> Div<T> for Vec2<T> {
+    type Output = Self;
+
+    /// Divides both components by a scalar.
+    fn div(self, scalar: T) -> Self {
+        Self {
+            x: self.x / scalar,
+            y: self.y / scalar,
+        }
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Vec2<T> {
+    type Output = Self;
+
+    /// Reverses the direction of the vector.
+    fn neg(self) -> Self {
+        Self {
+            x: -self.x,
+            y: -self.y,
+        }
+    }
+}
+
+impl<T: AddAssign> AddAssign for Vec2<T> {
+    /// In-place vector addition.
+    fn add_assign(&mut self, other: Vec2<T>) {
+        self.x += other.x;
+        self.y += other.y;
+    }
+}
+
+impl<T: SubAssign> SubAssign for Vec2<T> {
+    /// In-place vector subtraction.
+    fn sub_assign(&mut self, other: Vec2<T>) {
+        self.x -= other.x;
+        self.y -= other.y;
+    }
+}
+
+impl Mul<Vec2<f64>> for f64 {
+    type Output = Vec2<f64>;
+
+    /// Multiplies both components by a scalar, e.g. `2.0 * v`.
+    fn mul(self, vector: Vec2<f64>) -> Vec2<f64> {
+        vector * self
+    }
+}
+
+impl Mul<Vec2<f32>> for f32 {
+    type Output = Vec2<f32>;
+
+    /// Multiplies both components by a scalar, e.g. `2.0 * v`.
+    fn mul(self, vector: Vec2<f32>) -> Vec2<f32> {
+        vector * self
+    }
+}
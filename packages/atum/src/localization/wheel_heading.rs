@@ -0,0 +1,124 @@
+//! Wheel Heading
+//!
+//! Estimates the robot's heading from the difference in distance traveled
+//! by two parallel wheels a known distance apart:
+//!
+//!     θ = (left - right) / track
+//!
+//! matching [`Drivetrain::angular_velocity`], where driving the left side
+//! forward turns the robot toward positive headings.
+//!
+//! The wheels can be a pair of parallel tracking wheels or the left and
+//! right sides of the drivetrain. Wheel slip makes this estimate drift, so
+//! it is used as a fallback when the IMU is unavailable and as a cross-check
+//! against the IMU to catch drift during long runs.
+//!
+//! [`Drivetrain::angular_velocity`]: crate::subsystems::drivetrain::Drivetrain::angular_velocity
+
+use log::{info, warn};
+use vexide::math::Angle;
+
+/// Heading estimator for two parallel wheels.
+pub struct WheelHeading {
+    /// Distance between the left and right wheels.
+    track: f64,
+
+    /// Rotation of the robot when the reference distances were taken.
+    reference: Angle,
+
+    /// Total distance of the left wheel at the reference.
+    left_start: f64,
+
+    /// Total distance of the right wheel at the reference.
+    right_start: f64,
+}
+
+impl WheelHeading {
+    /// Creates a new estimator with the robot at `rotation` when the wheels
+    /// have traveled `left` and `right`.
+    pub fn new(track: f64, left: f64, right: f64, rotation: Angle) -> Self {
+        Self {
+            track,
+            reference: rotation,
+            left_start: left,
+            right_start: right,
+        }
+    }
+
+    /// Returns the robot rotation given the total distance of each wheel.
+    pub fn rotation(&self, left: f64, right: f64) -> Angle {
+        let left = left - self.left_start;
+        let right = right - self.right_start;
+
+        self.reference + Angle::from_radians((left - right) / self.track)
+    }
+
+    /// Resets the estimate so that the current wheel distances correspond
+    /// to `rotation`.
+    pub fn reset(&mut self, left: f64, right: f64, rotation: Angle) {
+        self.reference = rotation;
+        self.left_start = left;
+        self.right_start = right;
+    }
+}
+
+/// Compares two heading sources and logs when they diverge.
+pub struct HeadingCheck {
+    /// Name of the source being checked, used in log messages.
+    name: &'static str,
+
+    /// Largest difference allowed before the sources are considered diverged.
+    max_divergence: Angle,
+
+    /// Difference between the sources when the check started.
+    offset: Option<Angle>,
+
+    /// Whether the sources were diverged on the previous check.
+    diverged: bool,
+}
+
+impl HeadingCheck {
+    /// Creates a new check that warns when the sources differ by more
+    /// than `max_divergence`.
+    pub fn new(name: &'static str, max_divergence: Angle) -> Self {
+        Self {
+            name,
+            max_divergence,
+            offset: None,
+            diverged: false,
+        }
+    }
+
+    /// Compares the IMU rotation to the rotation of the source being
+    /// checked and returns the divergence since the check started.
+    ///
+    /// A warning is logged when the divergence first exceeds the limit and
+    /// a message is logged when it recovers.
+    pub fn check(&mut self, imu: Angle, other: Angle) -> Angle {
+        let difference = imu - other;
+        let offset = *self.offset.get_or_insert(difference);
+        let divergence = (difference - offset).wrapped_half();
+
+        let diverged = divergence.abs() > self.max_divergence;
+        if diverged != self.diverged {
+            if diverged {
+                warn!(
+                    "IMU and {} heading diverged by {:.2} deg",
+                    self.name,
+                    divergence.as_degrees()
+                );
+            } else {
+                info!("IMU and {} heading agree again", self.name);
+            }
+        }
+        self.diverged = diverged;
+
+        divergence
+    }
+
+    /// Forgets the stored offset, e.g. after the pose has been reset.
+    pub fn reset(&mut self) {
+        self.offset = None;
+        self.diverged = false;
+    }
+}
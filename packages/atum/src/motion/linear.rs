@@ -1,10 +1,14 @@
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
 use log::debug;
-use vexide::time::sleep;
 
 use super::{MotionError, MotionParameters, MotionResult};
-use crate::{controllers::pid::Pid, localization::vec2::Vec2, subsystems::drivetrain::Drivetrain};
+use crate::{
+    clock::{self, sleep},
+    controllers::pid::Pid,
+    localization::vec2::Vec2,
+    subsystems::drivetrain::Drivetrain,
+};
 
 /// Linear motion controller.
 ///
@@ -35,7 +39,7 @@ impl Linear {
         reverse: bool,
     ) -> MotionResult<f64> {
         let pose = drivetrain.pose().position();
-        let mut target_distance = (point - pose).length();
+        let mut target_distance = pose.distance_to(point);
         debug!("distance {}", target_distance);
 
         if reverse {
@@ -53,7 +57,7 @@ impl Linear {
         drivetrain: &mut Drivetrain,
         target: f64,
     ) -> MotionResult<f64> {
-        let start_time = Instant::now();
+        let start_time = clock::now();
         let mut prev_time = start_time;
 
         // Estimated distance traveled during this motion
@@ -66,7 +70,7 @@ impl Linear {
             // Run controller at 100 Hz
             sleep(Duration::from_millis(10)).await;
 
-            let now = Instant::now();
+            let now = clock::now();
             let dt = now - prev_time;
             prev_time = now;
 
@@ -96,7 +100,7 @@ impl Linear {
             if self
                 .params
                 .timeout
-                .is_some_and(|timeout| start_time.elapsed() > timeout)
+                .is_some_and(|timeout| clock::elapsed(start_time) > timeout)
             {
                 drivetrain.set_voltages(0.0, 0.0);
                 return Err(MotionError::Timeout(error));
@@ -7,6 +7,9 @@ pub mod move_to;
 pub mod swing;
 pub mod turn;
 
+#[cfg(test)]
+mod tests;
+
 use std::time::Duration;
 
 /// Common configuration parameters used by motion controllers.
@@ -1,10 +1,13 @@
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
-use vexide::{math::Angle, time::sleep};
+use vexide::math::Angle;
 
 use super::{MotionError, MotionParameters, MotionResult};
 use crate::{
-    controllers::pid::Pid, localization::vec2::Vec2, subsystems::drivetrain::Drivetrain,
+    clock::{self, sleep},
+    controllers::pid::Pid,
+    localization::vec2::Vec2,
+    subsystems::drivetrain::Drivetrain,
     utils::desaturate,
 };
 
@@ -45,7 +48,7 @@ impl MoveTo {
         drivetrain: &mut Drivetrain,
         target: Vec2<f64>,
     ) -> MotionResult<Vec2<f64>> {
-        let start_time = Instant::now();
+        let start_time = clock::now();
         let mut prev_time = start_time;
 
         // Reset PID parameters
@@ -56,7 +59,7 @@ impl MoveTo {
             // Run control loop at 100Hz
             sleep(Duration::from_millis(10)).await;
 
-            let now = Instant::now();
+            let now = clock::now();
             let dt = now - prev_time;
             prev_time = now;
 
@@ -93,7 +96,7 @@ impl MoveTo {
             if self
                 .params
                 .timeout
-                .is_some_and(|timeout| start_time.elapsed() > timeout)
+                .is_some_and(|timeout| clock::elapsed(start_time) > timeout)
             {
                 drivetrain.set_voltages(0.0, 0.0);
                 return Err(MotionError::Timeout(position_error));
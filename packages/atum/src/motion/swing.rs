@@ -1,9 +1,13 @@
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
-use vexide::{math::Angle, time::sleep};
+use vexide::math::Angle;
 
 use super::{MotionError, MotionParameters, MotionResult};
-use crate::{controllers::pid::Pid, subsystems::drivetrain::Drivetrain};
+use crate::{
+    clock::{self, sleep},
+    controllers::pid::Pid,
+    subsystems::drivetrain::Drivetrain,
+};
 
 /// Controller for performing a swing turn.
 ///
@@ -36,7 +40,7 @@ impl Swing {
         target: Angle,
         radius: f64,
     ) -> MotionResult<Angle> {
-        let start_time = Instant::now();
+        let start_time = clock::now();
         let mut prev_time = start_time;
 
         // Distance between the left and right wheels
@@ -49,7 +53,7 @@ impl Swing {
             // Run control loop at 100 Hz
             sleep(Duration::from_millis(10)).await;
 
-            let now = Instant::now();
+            let now = clock::now();
             let dt = now - prev_time;
             prev_time = now;
 
@@ -83,15 +87,17 @@ impl Swing {
             if self
                 .params
                 .timeout
-                .is_some_and(|timeout| start_time.elapsed() > timeout)
+                .is_some_and(|timeout| clock::elapsed(start_time) > timeout)
             {
                 drivetrain.set_voltages(0.0, 0.0);
                 return Err(MotionError::Timeout(error));
             }
 
             // Compute wheel velocities required to follow a circular arc.
-            let left = output * (radius - length / 2.0);
-            let right = output * (radius + length / 2.0);
+            // A positive output turns toward positive headings, the same way
+            // as `Turn`.
+            let left = output * (radius + length / 2.0);
+            let right = output * (radius - length / 2.0);
 
             // Apply wheel velocities
             drivetrain.set_velocity(left * self.params.speed, right * self.params.speed);
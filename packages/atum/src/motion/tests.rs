@@ -0,0 +1,460 @@
+//! Regression tests for the motion controllers, run against a simulated
+//! drivetrain with the gains the robots use.
+
+use std::time::Duration;
+
+use vexide::{math::Angle, runtime::block_on};
+
+use super::{
+    MotionError, MotionParameters, linear::Linear, move_to::MoveTo, swing::Swing, turn::Turn,
+};
+use crate::{
+    controllers::pid::Pid,
+    hardware::imu::Imu,
+    localization::{pose::Pose, vec2::Vec2},
+    simulation::{DrivetrainParameters, Simulation},
+    subsystems::drivetrain::Drivetrain,
+};
+
+/// `LINEAR_PID` and `ANGULAR_PID` of each robot package.
+const GAINS: [(&str, Pid, Pid); 3] = [
+    (
+        "black/champ/white",
+        Pid::new(0.06, 0.02, 0.004, 5.0),
+        Pid::new(0.78, 0.05, 0.05, 13.5),
+    ),
+    (
+        "cplusplus",
+        Pid::new(0.08, 0.04, 0.009, 8.0),
+        Pid::new(2.175, 0.2, 0.25, 10.0),
+    ),
+    (
+        "pink",
+        Pid::new(1.17 / 12.0, 0.0, 0.1 / 12.0, 0.3),
+        Pid::new(17.0 / 12.0, 0.75 / 12.0, 1.1 / 12.0, 40.0),
+    ),
+];
+
+/// Gains and radius pink's autons swing with (`rushcontrol`).
+const PINK_SWING: (Pid, f64) = (Pid::new(1000.0, 150.0, 0.0, 90.0), 5.0);
+
+/// Lateral gains used with `MoveTo` (white).
+const LATERAL_PID: Pid = Pid::new(0.08, 0.0, 0.005, 0.0);
+
+/// Time a motion is given to reach its target.
+const TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Start of every test, in the middle of the field facing +x.
+const START: Pose = Pose {
+    x: 72.0,
+    y: 72.0,
+    h: Angle::ZERO,
+    vf: 0.0,
+    vs: 0.0,
+    omega: 0.0,
+};
+
+/// Creates a simulated robot with a forward and a lateral tracking wheel and
+/// an IMU.
+fn simulated_robot() -> (Simulation, Drivetrain) {
+    let sim = Simulation::new(DrivetrainParameters::default(), START);
+    let forward = sim.tracking_wheel(2.0, Vec2::new(0.0, -1.0), Angle::ZERO);
+    let lateral = sim.tracking_wheel(2.0, Vec2::new(-2.0, 0.0), Angle::QUARTER_TURN);
+    let drivetrain = sim.drivetrain(vec![forward, lateral], Some(sim.imu()));
+
+    (sim, drivetrain)
+}
+
+fn heading_error(target: Angle, pose: Pose) -> f64 {
+    (target - pose.h).wrapped_half().as_degrees().abs()
+}
+
+#[test]
+fn drive_distance_reaches_target() {
+    block_on(async {
+        for (name, linear_pid, _) in GAINS {
+            let (sim, mut dt) = simulated_robot();
+            let mut linear = Linear::new(
+                linear_pid,
+                MotionParameters {
+                    tolerance: 0.5,
+                    timeout: Some(TIMEOUT),
+                    ..Default::default()
+                },
+            );
+
+            let (result, elapsed) = sim.run(linear.drive_distance(&mut dt, 24.0)).await;
+            let pose = sim.pose();
+
+            assert!(result.is_ok(), "{name}: timed out");
+            assert!(elapsed < TIMEOUT, "{name}: took {elapsed:?}");
+            assert!(
+                (pose.x - 96.0).abs() < 1.5,
+                "{name}: stopped at x = {}",
+                pose.x
+            );
+            assert!(
+                (pose.y - 72.0).abs() < 0.5,
+                "{name}: drifted to y = {}",
+                pose.y
+            );
+        }
+    });
+}
+
+#[test]
+fn turn_to_reaches_target() {
+    block_on(async {
+        for (name, _, angular_pid) in GAINS {
+            let (sim, mut dt) = simulated_robot();
+            let mut turn = Turn::new(
+                angular_pid,
+                MotionParameters {
+                    tolerance: Angle::from_degrees(1.0),
+                    timeout: Some(TIMEOUT),
+                    ..Default::default()
+                },
+            );
+
+            let target = Angle::from_degrees(90.0);
+            let (result, elapsed) = sim.run(turn.turn_to(&mut dt, target)).await;
+            let pose = sim.pose();
+
+            assert!(result.is_ok(), "{name}: timed out");
+            assert!(elapsed < TIMEOUT, "{name}: took {elapsed:?}");
+            assert!(
+                heading_error(target, pose) < 2.0,
+                "{name}: stopped at {} deg",
+                pose.h.as_degrees()
+            );
+            assert!(
+                pose.position().distance_to(START.position()) < 1.0,
+                "{name}: moved while turning"
+            );
+        }
+    });
+}
+
+// Swings stop as soon as the heading is within tolerance, while the robot is
+// still turning quickly, so the pose they stop on can trail the simulated
+// robot by one odometry update.
+
+#[test]
+fn swing_to_reaches_target() {
+    block_on(async {
+        for (name, _, angular_pid) in GAINS {
+            let (sim, mut dt) = simulated_robot();
+            let mut swing = Swing::new(
+                angular_pid,
+                MotionParameters {
+                    tolerance: Angle::from_degrees(1.0),
+                    timeout: Some(TIMEOUT),
+                    ..Default::default()
+                },
+            );
+
+            let target = Angle::from_degrees(90.0);
+            let (result, elapsed) = sim.run(swing.swing_to(&mut dt, target, 12.0)).await;
+            let pose = sim.pose();
+
+            assert!(result.is_ok(), "{name}: timed out");
+            assert!(elapsed < TIMEOUT, "{name}: took {elapsed:?}");
+            assert!(
+                heading_error(target, pose) < 4.0,
+                "{name}: stopped at {} deg",
+                pose.h.as_degrees()
+            );
+            // A swing moves the robot along an arc rather than turning in place
+            assert!(pose.x > START.x + 6.0, "{name}: turned in place");
+            assert!(pose.y > START.y + 6.0, "{name}: arced toward -y");
+        }
+    });
+}
+
+#[test]
+fn swing_to_reaches_target_with_pink_gains() {
+    block_on(async {
+        let (swing_pid, radius) = PINK_SWING;
+        let timeout = Duration::from_millis(750);
+
+        for degrees in [90.0, -90.0] {
+            let (sim, mut dt) = simulated_robot();
+            let mut swing = Swing::new(
+                swing_pid,
+                MotionParameters {
+                    tolerance: Angle::from_degrees(1.0),
+                    timeout: Some(timeout),
+                    ..Default::default()
+                },
+            );
+
+            let target = Angle::from_degrees(degrees);
+            let (result, elapsed) = sim.run(swing.swing_to(&mut dt, target, radius)).await;
+            let pose = sim.pose();
+
+            assert!(result.is_ok(), "pink: timed out swinging to {degrees} deg");
+            assert!(elapsed < timeout, "pink: took {elapsed:?}");
+            assert!(
+                heading_error(target, pose) < 4.0,
+                "pink: stopped at {} deg",
+                pose.h.as_degrees()
+            );
+        }
+    });
+}
+
+#[test]
+fn swing_to_current_heading_finishes_immediately() {
+    block_on(async {
+        let (name, _, angular_pid) = GAINS[0];
+        let (sim, mut dt) = simulated_robot();
+        let mut swing = Swing::new(
+            angular_pid,
+            MotionParameters {
+                tolerance: Angle::from_degrees(1.0),
+                timeout: Some(TIMEOUT),
+                ..Default::default()
+            },
+        );
+
+        let (result, elapsed) = sim.run(swing.swing_to(&mut dt, START.h, 12.0)).await;
+
+        assert!(result.is_ok(), "{name}: timed out");
+        assert!(
+            elapsed < Duration::from_millis(50),
+            "{name}: took {elapsed:?}"
+        );
+        assert!(
+            sim.pose().position().distance_to(START.position()) < 0.1,
+            "{name}: moved"
+        );
+    });
+}
+
+#[test]
+fn move_to_point_reaches_target() {
+    block_on(async {
+        for (name, linear_pid, _) in GAINS {
+            let (sim, mut dt) = simulated_robot();
+            let mut move_to = MoveTo::new(
+                linear_pid,
+                LATERAL_PID,
+                MotionParameters {
+                    tolerance: 1.0,
+                    timeout: Some(TIMEOUT),
+                    ..Default::default()
+                },
+            );
+
+            let target = Vec2::new(96.0, 78.0);
+            let (result, elapsed) = sim.run(move_to.move_to_point(&mut dt, target)).await;
+            let pose = sim.pose();
+
+            assert!(result.is_ok(), "{name}: timed out");
+            assert!(elapsed < TIMEOUT, "{name}: took {elapsed:?}");
+            assert!(
+                pose.position().distance_to(target) < 2.0,
+                "{name}: stopped at ({}, {})",
+                pose.x,
+                pose.y
+            );
+        }
+    });
+}
+
+// The residual of a timed out motion comes from odometry, which runs every
+// 10ms, so it can lag the simulated robot by up to one update.
+
+#[test]
+fn drive_distance_timeout_reports_remaining_distance() {
+    block_on(async {
+        let (name, linear_pid, _) = GAINS[0];
+        let (sim, mut dt) = simulated_robot();
+        let timeout = Duration::from_millis(250);
+        let mut linear = Linear::new(
+            linear_pid,
+            MotionParameters {
+                tolerance: 0.5,
+                timeout: Some(timeout),
+                ..Default::default()
+            },
+        );
+
+        let (result, elapsed) = sim.run(linear.drive_distance(&mut dt, 48.0)).await;
+        let Err(MotionError::Timeout(residual)) = result else {
+            panic!("{name}: expected a timeout");
+        };
+        let remaining = 48.0 - (sim.pose().x - START.x);
+
+        assert!(elapsed >= timeout && elapsed < timeout + Duration::from_millis(30));
+        assert!(
+            (residual - remaining).abs() < 1.5,
+            "{name}: residual {residual}, {remaining} remaining"
+        );
+    });
+}
+
+#[test]
+fn turn_to_timeout_reports_remaining_angle() {
+    block_on(async {
+        let (name, _, angular_pid) = GAINS[0];
+        let (sim, mut dt) = simulated_robot();
+        let timeout = Duration::from_millis(150);
+        let mut turn = Turn::new(
+            angular_pid,
+            MotionParameters {
+                tolerance: Angle::from_degrees(1.0),
+                timeout: Some(timeout),
+                ..Default::default()
+            },
+        );
+
+        let target = Angle::from_degrees(120.0);
+        let (result, elapsed) = sim.run(turn.turn_to(&mut dt, target)).await;
+        let Err(MotionError::Timeout(residual)) = result else {
+            panic!("{name}: expected a timeout");
+        };
+        let remaining = (target - sim.pose().h).wrapped_half();
+
+        assert!(elapsed >= timeout && elapsed < timeout + Duration::from_millis(30));
+        assert!(residual.as_degrees() > 0.0, "{name}: reached the target");
+        assert!(
+            (residual - remaining).wrapped_half().as_degrees().abs() < 5.0,
+            "{name}: residual {} deg, {} deg remaining",
+            residual.as_degrees(),
+            remaining.as_degrees()
+        );
+    });
+}
+
+#[test]
+fn swing_to_timeout_reports_remaining_angle() {
+    block_on(async {
+        let (name, _, angular_pid) = GAINS[0];
+        let (sim, mut dt) = simulated_robot();
+        let timeout = Duration::from_millis(150);
+        let mut swing = Swing::new(
+            angular_pid,
+            MotionParameters {
+                tolerance: Angle::from_degrees(1.0),
+                timeout: Some(timeout),
+                ..Default::default()
+            },
+        );
+
+        let target = Angle::from_degrees(120.0);
+        let (result, elapsed) = sim.run(swing.swing_to(&mut dt, target, 12.0)).await;
+        let Err(MotionError::Timeout(residual)) = result else {
+            panic!("{name}: expected a timeout");
+        };
+        let remaining = (target - sim.pose().h).wrapped_half();
+
+        assert!(elapsed >= timeout && elapsed < timeout + Duration::from_millis(30));
+        assert!(
+            (residual - remaining).wrapped_half().as_degrees().abs() < 5.0,
+            "{name}: residual {} deg, {} deg remaining",
+            residual.as_degrees(),
+            remaining.as_degrees()
+        );
+    });
+}
+
+#[test]
+fn move_to_point_timeout_reports_remaining_offset() {
+    block_on(async {
+        let (name, linear_pid, _) = GAINS[0];
+        let (sim, mut dt) = simulated_robot();
+        let timeout = Duration::from_millis(250);
+        let mut move_to = MoveTo::new(
+            linear_pid,
+            LATERAL_PID,
+            MotionParameters {
+                tolerance: 1.0,
+                timeout: Some(timeout),
+                ..Default::default()
+            },
+        );
+
+        let target = Vec2::new(120.0, 72.0);
+        let (result, elapsed) = sim.run(move_to.move_to_point(&mut dt, target)).await;
+        let Err(MotionError::Timeout(residual)) = result else {
+            panic!("{name}: expected a timeout");
+        };
+        let remaining = target - sim.pose().position();
+
+        assert!(elapsed >= timeout && elapsed < timeout + Duration::from_millis(30));
+        assert!(
+            residual.distance_to(remaining) < 1.5,
+            "{name}: residual ({}, {}), ({}, {}) remaining",
+            residual.x,
+            residual.y,
+            remaining.x,
+            remaining.y
+        );
+    });
+}
+
+#[test]
+fn encoder_heading_matches_imu_through_turn() {
+    block_on(async {
+        let (name, _, angular_pid) = GAINS[0];
+        let (sim, mut dt) = simulated_robot();
+        let mut turn = Turn::new(
+            angular_pid,
+            MotionParameters {
+                tolerance: Angle::from_degrees(1.0),
+                timeout: Some(TIMEOUT),
+                ..Default::default()
+            },
+        );
+
+        let (result, _) = sim
+            .run(turn.turn_to(&mut dt, Angle::from_degrees(90.0)))
+            .await;
+        assert!(result.is_ok(), "{name}: timed out");
+
+        let imu = dt.pose().h;
+        let encoder = dt.encoder_heading();
+        assert!(
+            (imu - encoder).wrapped_half().as_degrees().abs() < 3.0,
+            "{name}: IMU at {} deg, encoders at {} deg",
+            imu.as_degrees(),
+            encoder.as_degrees()
+        );
+    });
+}
+
+#[test]
+fn odometry_falls_back_to_encoder_heading() {
+    block_on(async {
+        let (name, _, angular_pid) = GAINS[0];
+        let sim = Simulation::new(DrivetrainParameters::default(), START);
+        let forward = sim.tracking_wheel(2.0, Vec2::new(0.0, -1.0), Angle::ZERO);
+        let lateral = sim.tracking_wheel(2.0, Vec2::new(-2.0, 0.0), Angle::QUARTER_TURN);
+        let sensor = sim.model().borrow_mut().add_imu();
+        let imu = Imu::new(vec![sensor.clone()], 1.0);
+        let mut dt = sim.drivetrain(vec![forward, lateral], Some(imu));
+
+        sensor.update(|state| state.connected = false);
+
+        let mut turn = Turn::new(
+            angular_pid,
+            MotionParameters {
+                tolerance: Angle::from_degrees(1.0),
+                timeout: Some(TIMEOUT),
+                ..Default::default()
+            },
+        );
+
+        let target = Angle::from_degrees(90.0);
+        let (result, _) = sim.run(turn.turn_to(&mut dt, target)).await;
+        let pose = sim.pose();
+
+        assert!(result.is_ok(), "{name}: timed out");
+        assert!(
+            heading_error(target, pose) < 5.0,
+            "{name}: stopped at {} deg",
+            pose.h.as_degrees()
+        );
+    });
+}
@@ -1,9 +1,14 @@
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
-use vexide::{math::Angle, prelude::Motor, smart::motor::BrakeMode, time::sleep};
+use vexide::{math::Angle, prelude::Motor, smart::motor::BrakeMode};
 
 use super::{MotionError, MotionParameters, MotionResult};
-use crate::{controllers::pid::Pid, localization::vec2::Vec2, subsystems::drivetrain::Drivetrain};
+use crate::{
+    clock::{self, sleep},
+    controllers::pid::Pid,
+    localization::vec2::Vec2,
+    subsystems::drivetrain::Drivetrain,
+};
 
 /// Controller responsible for rotational robot movement.
 ///
@@ -51,7 +56,7 @@ impl Turn {
         drivetrain: &mut Drivetrain,
         target: Angle,
     ) -> MotionResult<Angle> {
-        let start_time = Instant::now();
+        let start_time = clock::now();
         let mut prev_time = start_time;
 
         // Reset PID parameters
@@ -61,7 +66,7 @@ impl Turn {
             // Run controller loop at 100 Hz
             sleep(Duration::from_millis(10)).await;
 
-            let now = Instant::now();
+            let now = clock::now();
             let dt = now - prev_time;
             prev_time = now;
 
@@ -92,7 +97,7 @@ impl Turn {
             if self
                 .params
                 .timeout
-                .is_some_and(|timeout| start_time.elapsed() > timeout)
+                .is_some_and(|timeout| clock::elapsed(start_time) > timeout)
             {
                 drivetrain.brake(BrakeMode::Brake);
                 return Err(MotionError::Timeout(error));
@@ -0,0 +1,206 @@
+//! Simulation
+//!
+//! Runs the library's motion and localization code against a physics model
+//! of a differential drivetrain instead of a robot.
+//!
+//! A [`Simulation`] owns a [`DrivetrainModel`] and hands out hardware
+//! wrappers backed by [`mock`](crate::hardware::mock) devices attached to it:
+//! motor groups, tracking wheels, IMUs and distance sensors. A background
+//! task steps the model and advances the [`clock`](crate::clock), so
+//! motions see realistic sensor readings and run in virtual time.
+//!
+//! ```ignore
+//! let sim = Simulation::new(DrivetrainParameters::default(), Pose::default());
+//! let wheel = sim.tracking_wheel(2.0, Vec2::new(0.0, 0.0), Angle::ZERO);
+//! let mut dt = sim.drivetrain(vec![wheel], Some(sim.imu()));
+//!
+//! let (result, elapsed) = sim.run(linear.drive_distance(&mut dt, 24.0)).await;
+//! ```
+
+pub mod model;
+
+use std::{cell::RefCell, future::Future, ops::Range, rc::Rc, time::Duration};
+
+use vexide::{
+    math::Angle,
+    task::{Task, spawn},
+};
+
+pub use self::model::{DrivetrainModel, DrivetrainParameters};
+use crate::{
+    clock,
+    hardware::{
+        imu::Imu, motor_group::MotorGroup, tracking_wheel::TrackingWheel,
+        wall_distance_sensor::WallDistanceSensor,
+    },
+    localization::{odometry::Odometry, pose::Pose, pose_handle::PoseHandle, vec2::Vec2},
+    subsystems::drivetrain::Drivetrain,
+};
+
+/// Time simulated per physics step.
+const STEP: Duration = Duration::from_millis(1);
+
+/// Time between samples of the recorded trace.
+const TRACE_PERIOD: Duration = Duration::from_millis(10);
+
+/// Range of object sizes simulated distance sensors report as walls.
+const WALL_SIZE: Range<u32> = 0..400;
+
+/// True pose of the simulated robot at a point in time.
+#[derive(Debug, Clone, Copy)]
+pub struct TraceSample {
+    /// Time since the simulation started.
+    pub time: Duration,
+
+    /// Pose of the robot.
+    pub pose: Pose,
+}
+
+/// A simulated robot running in virtual time.
+///
+/// Creating a simulation switches the [`clock`] to virtual time until it is
+/// dropped.
+pub struct Simulation {
+    model: Rc<RefCell<DrivetrainModel>>,
+    elapsed: Rc<RefCell<Duration>>,
+    trace: Rc<RefCell<Vec<TraceSample>>>,
+    _task: Task<()>,
+}
+
+impl Simulation {
+    /// Creates a simulated robot at rest at `pose` and starts stepping it.
+    pub fn new(params: DrivetrainParameters, pose: Pose) -> Self {
+        clock::use_virtual_time();
+
+        let model = Rc::new(RefCell::new(DrivetrainModel::new(params, pose)));
+        let elapsed = Rc::new(RefCell::new(Duration::ZERO));
+        let trace = Rc::new(RefCell::new(vec![TraceSample {
+            time: Duration::ZERO,
+            pose,
+        }]));
+
+        let task = spawn({
+            let model = model.clone();
+            let elapsed = elapsed.clone();
+            let trace = trace.clone();
+            async move {
+                let mut since_sample = Duration::ZERO;
+                loop {
+                    model.borrow_mut().step(STEP.as_secs_f64());
+                    clock::advance(STEP);
+
+                    let time = {
+                        let mut elapsed = elapsed.borrow_mut();
+                        *elapsed += STEP;
+                        *elapsed
+                    };
+
+                    since_sample += STEP;
+                    if since_sample >= TRACE_PERIOD {
+                        since_sample = Duration::ZERO;
+                        trace.borrow_mut().push(TraceSample {
+                            time,
+                            pose: model.borrow().pose(),
+                        });
+                    }
+
+                    clock::yield_now().await;
+                }
+            }
+        });
+
+        Self {
+            model,
+            elapsed,
+            trace,
+            _task: task,
+        }
+    }
+
+    /// Returns the physics model of the robot.
+    pub fn model(&self) -> Rc<RefCell<DrivetrainModel>> {
+        self.model.clone()
+    }
+
+    /// Returns the true pose of the robot.
+    pub fn pose(&self) -> Pose {
+        self.model.borrow().pose()
+    }
+
+    /// Returns the virtual time since the simulation started.
+    pub fn elapsed(&self) -> Duration {
+        *self.elapsed.borrow()
+    }
+
+    /// Returns the true pose of the robot every 10ms since the simulation
+    /// started.
+    pub fn trace(&self) -> Vec<TraceSample> {
+        self.trace.borrow().clone()
+    }
+
+    /// Returns the left and right drive motor groups.
+    ///
+    /// The groups are geared like the simulated drive, so their output
+    /// positions are wheel rotations.
+    pub fn motor_groups(&self) -> (MotorGroup, MotorGroup) {
+        let model = self.model.borrow();
+        let [left, right] = model.motors();
+        let [mut left, mut right] = [MotorGroup::new(left, None), MotorGroup::new(right, None)];
+        left.set_gear_ratio(model.params().gear_ratio);
+        right.set_gear_ratio(model.params().gear_ratio);
+
+        (left, right)
+    }
+
+    /// Attaches a tracking wheel to the robot.
+    pub fn tracking_wheel(
+        &self,
+        wheel_diameter: f64,
+        from_center: Vec2<f64>,
+        angle: Angle,
+    ) -> TrackingWheel {
+        let encoder =
+            self.model
+                .borrow_mut()
+                .add_tracking_wheel(wheel_diameter, from_center, angle);
+
+        TrackingWheel::with_encoder(encoder, wheel_diameter, from_center, angle)
+    }
+
+    /// Attaches an inertial sensor to the robot.
+    pub fn imu(&self) -> Imu {
+        Imu::new(vec![self.model.borrow_mut().add_imu()], 1.0)
+    }
+
+    /// Attaches a distance sensor to the robot at `offset`, pointing at
+    /// `angle`.
+    pub fn distance_sensor(&self, offset: Vec2<f64>, angle: Angle) -> WallDistanceSensor {
+        let sensor = self.model.borrow_mut().add_distance_sensor(offset, angle);
+        WallDistanceSensor::with_device(sensor, offset, angle, WALL_SIZE)
+    }
+
+    /// Creates a drivetrain on the simulated motors, localized by odometry
+    /// with the given sensors.
+    pub fn drivetrain(&self, wheels: Vec<TrackingWheel>, imu: Option<Imu>) -> Drivetrain {
+        let params = self.model.borrow().params();
+        let (left, right) = self.motor_groups();
+        let odometry = Odometry::new(PoseHandle::new(self.pose()), wheels, imu);
+
+        Drivetrain::new(left, right, odometry, params.wheel_diameter, params.track)
+    }
+
+    /// Runs `future` to completion, returning its output and the virtual
+    /// time it took.
+    pub async fn run<F: Future>(&self, future: F) -> (F::Output, Duration) {
+        let start = self.elapsed();
+        let output = future.await;
+
+        (output, self.elapsed() - start)
+    }
+}
+
+impl Drop for Simulation {
+    fn drop(&mut self) {
+        clock::use_real_time();
+    }
+}
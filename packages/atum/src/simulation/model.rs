@@ -0,0 +1,401 @@
+//! Differential drivetrain physics.
+//!
+//! Each side of the drivetrain is driven by a group of DC motors with a
+//! linear torque/speed curve. The force a side can put into the ground is
+//! limited by traction; past that limit the wheels spin faster than the
+//! ground moves under them, which shows up on the drive encoders but not on
+//! the tracking wheels or IMU, as on a real robot.
+//!
+//! Geometry is in inches to match the rest of the library. Mass, force and
+//! torque are in SI units.
+//!
+//! Headings follow the motion commands: driving the left side forward and
+//! the right side backward (a positive `turn` in
+//! [`Drivetrain::set_arcade`](crate::subsystems::drivetrain::Drivetrain::set_arcade))
+//! turns the robot toward positive headings.
+
+use std::f64::consts::{PI, TAU};
+
+use vexide::{math::Angle, smart::motor::BrakeMode};
+
+use crate::{
+    hardware::{
+        mock::{MockDistanceSensor, MockEncoder, MockImu, MockMotor},
+        wall_distance_sensor::raycast,
+    },
+    localization::{odometry::WheelGeometry, pose::Pose, rcl::RclParameters, vec2::Vec2},
+};
+
+const METERS_PER_INCH: f64 = 0.0254;
+const GRAVITY: f64 = 9.81;
+
+/// Physical properties of a simulated drivetrain.
+#[derive(Debug, Clone, Copy)]
+pub struct DrivetrainParameters {
+    /// Distance between the left and right wheels (inches).
+    pub track: f64,
+
+    /// Diameter of the drive wheels (inches).
+    pub wheel_diameter: f64,
+
+    /// Wheel rotations per motor rotation.
+    pub gear_ratio: f64,
+
+    /// Number of motors on each side.
+    pub motors_per_side: usize,
+
+    /// Motor speed at 12 V with no load (RPM).
+    pub free_speed: f64,
+
+    /// Motor torque at 12 V when stalled (N·m).
+    pub stall_torque: f64,
+
+    /// Mass of the robot (kg).
+    pub mass: f64,
+
+    /// Moment of inertia of the robot about its center (kg·m²).
+    pub moment_of_inertia: f64,
+
+    /// Coefficient of friction between the wheels and the field.
+    pub friction: f64,
+
+    /// Mass of each side's rotating parts, as seen at the wheel surface (kg).
+    pub wheel_inertia: f64,
+
+    /// Resistance to driving forward (N per m/s).
+    pub linear_drag: f64,
+
+    /// Resistance to turning, e.g. wheel scrub (N·m per rad/s).
+    pub angular_drag: f64,
+
+    /// Rate the simulated IMU drifts (radians/sec).
+    pub imu_drift: f64,
+}
+
+impl Default for DrivetrainParameters {
+    /// A 15 lb, 12" track drive on six 600 RPM motors geared to 450 RPM with
+    /// 3.25" wheels.
+    fn default() -> Self {
+        Self {
+            track: 12.0,
+            wheel_diameter: 3.25,
+            gear_ratio: 0.75,
+            motors_per_side: 3,
+            free_speed: 600.0,
+            stall_torque: 0.35,
+            mass: 6.8,
+            moment_of_inertia: 0.22,
+            friction: 1.0,
+            wheel_inertia: 0.2,
+            linear_drag: 1.0,
+            angular_drag: 0.05,
+            imu_drift: 0.0,
+        }
+    }
+}
+
+/// One side of the drivetrain.
+#[derive(Default)]
+struct Side {
+    motors: Vec<MockMotor>,
+
+    /// Speed of the wheel surface (m/s).
+    speed: f64,
+
+    /// Whether the wheels are spinning relative to the ground.
+    slipping: bool,
+
+    /// Rotation of the motor shafts (radians).
+    position: f64,
+}
+
+/// A tracking wheel and the encoder it turns.
+struct SimulatedWheel {
+    encoder: MockEncoder,
+    geometry: WheelGeometry,
+    circumference: f64,
+    travel: f64,
+}
+
+/// A distance sensor and where it is mounted.
+struct SimulatedDistanceSensor {
+    sensor: MockDistanceSensor,
+    offset: Vec2<f64>,
+    angle: Angle,
+}
+
+/// The physical state of a simulated robot and the sensors attached to it.
+pub struct DrivetrainModel {
+    params: DrivetrainParameters,
+    field: RclParameters,
+
+    /// True pose of the robot. Velocities are robot-relative (in/s, rad/s).
+    pose: Pose,
+
+    /// Time simulated so far (seconds).
+    time: f64,
+
+    left: Side,
+    right: Side,
+    wheels: Vec<SimulatedWheel>,
+    imus: Vec<MockImu>,
+    distance_sensors: Vec<SimulatedDistanceSensor>,
+}
+
+impl DrivetrainModel {
+    /// Creates a robot at rest at `pose`.
+    pub fn new(params: DrivetrainParameters, pose: Pose) -> Self {
+        let side = || Side {
+            motors: (0..params.motors_per_side)
+                .map(|_| MockMotor::new())
+                .collect(),
+            ..Default::default()
+        };
+
+        Self {
+            params,
+            field: RclParameters::default(),
+            pose: Pose::new(pose.x, pose.y, pose.h),
+            time: 0.0,
+            left: side(),
+            right: side(),
+            wheels: Vec::new(),
+            imus: Vec::new(),
+            distance_sensors: Vec::new(),
+        }
+    }
+
+    /// Returns the physical properties of the drivetrain.
+    pub fn params(&self) -> DrivetrainParameters {
+        self.params
+    }
+
+    /// Returns the true pose of the robot.
+    pub fn pose(&self) -> Pose {
+        self.pose
+    }
+
+    /// Returns the motors on each side of the drivetrain.
+    pub fn motors(&self) -> [Vec<MockMotor>; 2] {
+        [self.left.motors.clone(), self.right.motors.clone()]
+    }
+
+    /// Returns whether each side's wheels are slipping.
+    pub fn slipping(&self) -> [bool; 2] {
+        [self.left.slipping, self.right.slipping]
+    }
+
+    /// Attaches a tracking wheel and returns the encoder it turns.
+    pub fn add_tracking_wheel(
+        &mut self,
+        diameter: f64,
+        from_center: Vec2<f64>,
+        angle: Angle,
+    ) -> MockEncoder {
+        let encoder = MockEncoder::new();
+        self.wheels.push(SimulatedWheel {
+            encoder: encoder.clone(),
+            geometry: WheelGeometry::new(from_center, angle),
+            circumference: diameter * PI,
+            travel: 0.0,
+        });
+
+        encoder
+    }
+
+    /// Attaches an inertial sensor.
+    pub fn add_imu(&mut self) -> MockImu {
+        let imu = MockImu::new();
+        imu.set_robot_rotation(self.pose.h);
+        self.imus.push(imu.clone());
+
+        imu
+    }
+
+    /// Attaches a distance sensor at `offset` pointing at `angle`.
+    pub fn add_distance_sensor(&mut self, offset: Vec2<f64>, angle: Angle) -> MockDistanceSensor {
+        let sensor = MockDistanceSensor::new();
+        self.distance_sensors.push(SimulatedDistanceSensor {
+            sensor: sensor.clone(),
+            offset,
+            angle,
+        });
+        self.update_sensors(Vec2::new(0.0, 0.0), 0.0);
+
+        sensor
+    }
+
+    /// Advances the simulation by `dt` seconds.
+    pub fn step(&mut self, dt: f64) {
+        let params = self.params;
+        let half_track = params.track * METERS_PER_INCH / 2.0;
+        let wheel_radius = params.wheel_diameter * METERS_PER_INCH / 2.0;
+        let max_force = params.friction * params.mass * GRAVITY / 2.0;
+
+        let v = self.pose.vf * METERS_PER_INCH;
+        let omega = self.pose.omega;
+
+        // Ground speed under each side
+        let ground = [v + omega * half_track, v - omega * half_track];
+
+        let mut forces = [0.0; 2];
+        for (side, (ground, force)) in [&mut self.left, &mut self.right]
+            .into_iter()
+            .zip(ground.into_iter().zip(&mut forces))
+        {
+            *force = side.step(&params, wheel_radius, ground, max_force, dt);
+        }
+
+        // Body dynamics
+        let [left, right] = forces;
+        let acceleration = (left + right - params.linear_drag * v) / params.mass;
+        let angular_acceleration =
+            ((left - right) * half_track - params.angular_drag * omega) / params.moment_of_inertia;
+
+        let v_next = v + acceleration * dt;
+        let omega_next = omega + angular_acceleration * dt;
+
+        // Integrate the pose with the midpoint velocities
+        let distance = (v + v_next) / 2.0 * dt / METERS_PER_INCH;
+        let dh = (omega + omega_next) / 2.0 * dt;
+        let delta = Vec2::new(distance, 0.0);
+        let global = delta.rotated(self.pose.h.as_radians() + dh / 2.0);
+
+        self.pose = Pose {
+            x: self.pose.x + global.x,
+            y: self.pose.y + global.y,
+            h: self.pose.h + Angle::from_radians(dh),
+            vf: v_next / METERS_PER_INCH,
+            vs: 0.0,
+            omega: omega_next,
+        };
+        self.time += dt;
+
+        for side in [&mut self.left, &mut self.right] {
+            side.update_motors(&params, wheel_radius, dt);
+        }
+        self.update_sensors(delta, dh);
+    }
+
+    /// Moves the sensors to match the robot's motion of `delta` (robot frame)
+    /// and `dh` radians.
+    fn update_sensors(&mut self, delta: Vec2<f64>, dh: f64) {
+        for wheel in &mut self.wheels {
+            wheel.travel += wheel.geometry.axis.dot(delta) + dh * wheel.geometry.lever;
+            wheel.encoder.set_position(Some(Angle::from_radians(
+                TAU * wheel.travel / wheel.circumference,
+            )));
+        }
+
+        let drift = Angle::from_radians(self.params.imu_drift * self.time);
+        for imu in &self.imus {
+            imu.set_robot_rotation(self.pose.h + drift);
+        }
+
+        for sensor in &self.distance_sensors {
+            let hit = raycast(
+                self.pose.to_field(sensor.offset),
+                self.pose.h + sensor.angle,
+                self.field.field_size,
+                self.field.max_raycast_dist,
+            );
+            match hit {
+                Some(hit) => sensor.sensor.set_distance(hit.distance),
+                None => sensor.sensor.set_reading(None),
+            }
+        }
+    }
+}
+
+impl Side {
+    /// Steps the wheels of this side and returns the force they put into the
+    /// ground (N).
+    fn step(
+        &mut self,
+        params: &DrivetrainParameters,
+        wheel_radius: f64,
+        ground: f64,
+        max_force: f64,
+        dt: f64,
+    ) -> f64 {
+        if !self.slipping {
+            self.speed = ground;
+
+            let force = self.motor_force(params, wheel_radius);
+            if force.abs() <= max_force {
+                return force;
+            }
+
+            self.slipping = true;
+        }
+
+        // Kinetic friction opposes the wheels spinning relative to the ground
+        let motor_force = self.motor_force(params, wheel_radius);
+        let slip = self.speed - ground;
+        let friction = if slip.abs() > 1e-6 {
+            max_force * slip.signum()
+        } else {
+            max_force * motor_force.signum()
+        };
+
+        self.speed += (motor_force - friction) / params.wheel_inertia * dt;
+
+        // Grip again once the wheels match the ground
+        if (self.speed - ground).signum() != slip.signum() && motor_force.abs() <= max_force {
+            self.slipping = false;
+            self.speed = ground;
+        }
+
+        friction
+    }
+
+    /// Returns the force the motors put on the wheel surface at its current
+    /// speed (N).
+    fn motor_force(&self, params: &DrivetrainParameters, wheel_radius: f64) -> f64 {
+        let motor_speed = self.speed / wheel_radius / params.gear_ratio;
+        let free_speed = params.free_speed / 60.0 * TAU;
+
+        let mut voltage = 0.0;
+        let mut coasting = 0;
+        for motor in &self.motors {
+            let state = motor.state();
+            voltage += match (state.brake, state.target_velocity) {
+                (Some(BrakeMode::Coast), _) => {
+                    coasting += 1;
+                    continue;
+                }
+                (Some(_), _) => 0.0,
+                // Approximate the motor's built-in velocity controller
+                (None, Some(rpm)) => {
+                    let target = rpm as f64 / 60.0 * TAU;
+                    (12.0 * target / free_speed + 2.0 * (target - motor_speed) / free_speed)
+                        .clamp(-12.0, 12.0)
+                }
+                (None, None) => state.voltage.clamp(-12.0, 12.0),
+            };
+        }
+
+        let powered = self.motors.len() - coasting;
+        if powered == 0 {
+            return 0.0;
+        }
+
+        // Linear DC motor curve, per powered motor
+        let torque =
+            params.stall_torque * (voltage / powered as f64 / 12.0 - motor_speed / free_speed);
+        powered as f64 * torque / params.gear_ratio / wheel_radius
+    }
+
+    /// Reports the wheel motion on the side's motor encoders.
+    fn update_motors(&mut self, params: &DrivetrainParameters, wheel_radius: f64, dt: f64) {
+        let velocity = self.speed / wheel_radius / params.gear_ratio;
+        self.position += velocity * dt;
+
+        for motor in &self.motors {
+            motor.update(|state| {
+                state.velocity = velocity / TAU * 60.0;
+                state.position = Angle::from_radians(self.position);
+            });
+        }
+    }
+}
@@ -6,13 +6,25 @@
 use std::f64::consts::PI;
 
 use vexide::{
+    math::Angle,
     prelude::{Gearset, Motor},
     smart::motor::BrakeMode,
 };
 
 use crate::{
-    hardware::motor_group::MotorGroup,
-    localization::{odometry::Odometry, pose::Pose},
+    hardware::{
+        battery::Battery,
+        encoder::MotorEncoder,
+        motor_group::{DeratingParameters, MotorGroup, MotorHealth},
+        tracking_wheel::TrackingWheel,
+    },
+    localization::{
+        odometry::Odometry,
+        pose::Pose,
+        pose_handle::PoseHandle,
+        vec2::Vec2,
+        wheel_heading::{HeadingCheck, WheelHeading},
+    },
     mappings::DriveMode,
     utils::{desaturate, apply_curve},
 };
@@ -25,29 +37,72 @@ pub struct Drivetrain {
     pub odometry: Odometry,
     wheel_circum: f64,
     track: f64,
+    encoder_heading: WheelHeading,
+    heading_check: HeadingCheck,
 }
 
 impl Drivetrain {
     /// Create a new drivetrain instance
+    ///
+    /// Unless the odometry already has a second heading source, the drive
+    /// motor encoders are used as one, so the odometry keeps a heading when
+    /// the IMU fails.
     pub fn new(
-        left: MotorGroup,
-        right: MotorGroup,
-        odometry: Odometry,
+        mut left: MotorGroup,
+        mut right: MotorGroup,
+        mut odometry: Odometry,
         wheel_diameter: f64,
         track: f64,
     ) -> Self {
+        left.set_name("left drive");
+        right.set_name("right drive");
+
+        if !odometry.has_heading_wheels() {
+            let side = |group: &MotorGroup, offset: f64| {
+                TrackingWheel::with_encoder(
+                    MotorEncoder::new(group, group.gear_ratio()),
+                    wheel_diameter,
+                    Vec2::new(0.0, offset),
+                    Angle::ZERO,
+                )
+            };
+            odometry.set_heading_wheels(
+                side(&left, track / 2.0),
+                side(&right, -track / 2.0),
+                track,
+            );
+        }
+
+        let wheel_circum = wheel_diameter * PI;
+        let encoder_heading = WheelHeading::new(
+            track,
+            wheel_circum * left.output_position().as_turns(),
+            wheel_circum * right.output_position().as_turns(),
+            odometry.pose().h,
+        );
+
         Self {
             left,
             right,
             odometry,
-            wheel_circum: wheel_diameter * PI,
+            wheel_circum,
             track,
+            encoder_heading,
+            heading_check: HeadingCheck::new("drive encoder", Angle::from_degrees(5.0)),
         }
     }
 
     /// Set motor voltages directly
+    ///
+    /// With battery compensation enabled the voltages are desaturated to
+    /// what the motors can still reach after compensation, so the ratio
+    /// between the sides is kept at full power.
     pub fn set_voltages(&mut self, left: f64, right: f64) {
-        let [left, right] = desaturate([left, right], 1.0);
+        let compensation = self
+            .left
+            .battery_compensation()
+            .max(self.right.battery_compensation());
+        let [left, right] = desaturate([left, right], 1.0 / compensation.max(1.0));
         self.left.set_voltage(left * Motor::V5_MAX_VOLTAGE);
         self.right.set_voltage(right * Motor::V5_MAX_VOLTAGE);
     }
@@ -127,9 +182,72 @@ impl Drivetrain {
         self.odometry.pose()
     }
 
+    /// Returns a handle to the shared robot pose
+    pub fn pose_handle(&self) -> PoseHandle {
+        self.odometry.pose_handle()
+    }
+
     /// Set the robot's pose
     pub fn set_pose(&mut self, pose: Pose) {
         self.odometry.set_pose(pose);
+
+        let [left, right] = self.distances();
+        self.encoder_heading.reset(left, right, pose.h);
+        self.heading_check.reset();
+    }
+
+    /// Returns the total distance traveled by each side of the drivetrain
+    pub fn distances(&self) -> [f64; 2] {
+        [
+            self.wheel_circum * self.left.output_position().as_turns(),
+            self.wheel_circum * self.right.output_position().as_turns(),
+        ]
+    }
+
+    /// Returns the robot heading estimated from the drive motor encoders
+    ///
+    /// This drifts with wheel slip but does not depend on the IMU.
+    pub fn encoder_heading(&self) -> Angle {
+        let [left, right] = self.distances();
+        self.encoder_heading.rotation(left, right)
+    }
+
+    /// Compares the odometry heading with the drive encoder heading, logging
+    /// a warning when they diverge, and returns the divergence
+    ///
+    /// Call this periodically during long runs (e.g. skills) to catch IMU drift.
+    pub fn check_heading(&mut self) -> Angle {
+        let encoder_heading = self.encoder_heading();
+        self.heading_check.check(self.pose().h, encoder_heading)
+    }
+
+    /// Sets how the drive motors lower their current limits as they heat up,
+    /// or disables derating with `None`
+    ///
+    /// Derating is disabled by default.
+    pub fn set_derating(&mut self, derating: Option<DeratingParameters>) {
+        self.left.set_derating(derating);
+        self.right.set_derating(derating);
+    }
+
+    /// Enables battery voltage compensation of the drive motors with
+    /// `battery`, or disables it with `None`
+    ///
+    /// Compensation is disabled by default.
+    pub fn set_battery_compensation(&mut self, battery: Option<Battery>) {
+        self.left.set_battery_compensation(battery.clone());
+        self.right.set_battery_compensation(battery);
+    }
+
+    /// Checks the health of every drive motor, logging any that disconnected,
+    /// faulted or overheated since the last check
+    ///
+    /// Returns `true` if every motor is healthy.
+    pub fn check_health(&mut self) -> bool {
+        let left = self.left.check_health();
+        let right = self.right.check_health();
+
+        left.iter().chain(&right).all(MotorHealth::is_healthy)
     }
 
     /// Returns the distance between left and right wheels
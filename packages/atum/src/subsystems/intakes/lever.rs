@@ -1,77 +1,290 @@
-use std::{cell::RefCell, rc::Rc, time::Duration};
+//! Lever Intake
+//!
+//! An intake roller paired with a lever that flips balls into a goal. A
+//! background task runs the lever through its stages using a rotation
+//! sensor on the lever's pivot:
+//!
+//! - `Score(start, end)` swings the lever up, ramping the voltage from
+//!   `start` to `end`, until it reaches the score angle, then resets.
+//! - `Reset` drives the lever down until it is below the reset angle.
+//! - `Idle` presses the lever down with a small voltage.
+//! - `Hold(angle)` holds the lever at an angle with a PID loop.
+//!
+//! If a ball jams the lever while it is moving, the lever is reset and the
+//! stall is reported through [`Lever::score_done`].
 
-use log::info;
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use log::warn;
 use vexide::{
     math::Angle,
-    prelude::{Motor, RotationSensor},
+    prelude::Motor,
     task::{Task, spawn},
-    time::sleep,
 };
 
-use crate::{hardware::motor_group::MotorGroup};
+use crate::{
+    clock::{self, sleep},
+    controllers::pid::Pid,
+    hardware::{devices::MotorDevice, encoder::Encoder, motor_group::MotorGroup},
+};
 
-#[derive(Copy, Clone)]
+/// Time between updates of the lever task.
+const PERIOD: Duration = Duration::from_millis(10);
+
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum LeverStage {
     Score(f64, f64),
     Reset,
     Idle,
+    Hold(Angle),
+}
+
+/// How the score voltage changes from `start` to `end` as the lever swings
+/// up.
+#[derive(Copy, Clone, Debug)]
+pub enum LeverRamp {
+    /// Stays at the start voltage.
+    Constant,
+
+    /// Changes linearly with the lever angle.
+    Linear,
+
+    /// Maps the fraction of the score angle reached (`0.0` to `1.0`) to the
+    /// fraction of the way from `start` to `end`.
+    Custom(fn(f64) -> f64),
+}
+
+impl LeverRamp {
+    fn voltage(&self, start: f64, end: f64, progress: f64) -> f64 {
+        let progress = progress.clamp(0.0, 1.0);
+        let fraction = match self {
+            Self::Constant => 0.0,
+            Self::Linear => progress,
+            Self::Custom(f) => f(progress).clamp(0.0, 1.0),
+        };
+
+        start + (end - start) * fraction
+    }
+}
+
+/// When the lever is considered jammed.
+#[derive(Copy, Clone, Debug)]
+pub struct StallParameters {
+    /// Speed below which the lever counts as not moving (degrees per second).
+    pub velocity: f64,
+
+    /// Voltage magnitude above which the lever is being driven (V).
+    pub voltage: f64,
+
+    /// How long the lever must be driven without moving to be stalled.
+    pub time: Duration,
+}
+
+impl Default for StallParameters {
+    fn default() -> Self {
+        Self {
+            velocity: 20.0,
+            voltage: 3.0,
+            time: Duration::from_millis(300),
+        }
+    }
+}
+
+/// Geometry and tuning of a lever.
+#[derive(Copy, Clone)]
+pub struct LeverParameters {
+    /// Angle at which a score is finished and the lever resets.
+    pub score_angle: Angle,
+
+    /// Angle below which a reset is finished and the lever idles.
+    pub reset_angle: Angle,
+
+    /// Voltage pressing the lever down while idle.
+    pub idle_voltage: f64,
+
+    /// Voltage driving the lever down while resetting.
+    pub reset_voltage: f64,
+
+    /// How long the lever keeps pushing after reaching the score angle.
+    pub score_pause: Duration,
+
+    /// How the score voltage changes as the lever swings up.
+    pub ramp: LeverRamp,
+
+    /// Whether the rotation sensor reads negative angles as the lever swings
+    /// up.
+    pub reversed: bool,
+
+    /// Position controller for `Hold`, with the error in degrees and the
+    /// output in volts.
+    pub pid: Pid,
+
+    /// Angle within which a `Hold` target is considered reached.
+    pub tolerance: Angle,
+
+    /// Stall detection, if enabled.
+    pub stall: Option<StallParameters>,
+}
+
+impl Default for LeverParameters {
+    fn default() -> Self {
+        Self {
+            score_angle: Angle::from_degrees(120.0),
+            reset_angle: Angle::from_degrees(5.0),
+            idle_voltage: -2.0,
+            reset_voltage: -Motor::V5_MAX_VOLTAGE,
+            score_pause: Duration::from_millis(50),
+            ramp: LeverRamp::Linear,
+            reversed: true,
+            pid: Pid::new(0.25, 0.0, 0.01, 0.0),
+            tolerance: Angle::from_degrees(3.0),
+            stall: Some(StallParameters::default()),
+        }
+    }
+}
+
+/// Error returned when waiting on the lever.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LeverError {
+    /// The lever jammed and was reset.
+    Stalled,
+}
+
+/// State shared between the lever and its task.
+struct LeverState {
+    stage: LeverStage,
+    angle: Angle,
+    stalled: bool,
 }
 
 pub struct Lever {
     voltage: Rc<RefCell<f64>>,
-    lever_stage: Rc<RefCell<LeverStage>>,
+    state: Rc<RefCell<LeverState>>,
+    params: Rc<RefCell<LeverParameters>>,
     _task: Task<()>,
 }
 
 impl Lever {
-    pub fn new(mut intake: Motor, mut lever: MotorGroup, rotation: RotationSensor) -> Self {
+    /// Creates a lever with the default parameters. `rotation` measures the
+    /// lever's pivot, e.g. a [`RotationSensor`](vexide::prelude::RotationSensor).
+    pub fn new(
+        intake: impl MotorDevice + 'static,
+        lever: MotorGroup,
+        rotation: impl Encoder + 'static,
+    ) -> Self {
+        Self::with_params(intake, lever, rotation, LeverParameters::default())
+    }
+
+    /// Creates a lever and starts the task running it.
+    pub fn with_params(
+        mut intake: impl MotorDevice + 'static,
+        mut lever: MotorGroup,
+        rotation: impl Encoder + 'static,
+        params: LeverParameters,
+    ) -> Self {
         let voltage = Rc::new(RefCell::new(0.0));
-        let lever_stage = Rc::new(RefCell::new(LeverStage::Idle));
+        let state = Rc::new(RefCell::new(LeverState {
+            stage: LeverStage::Idle,
+            angle: Angle::ZERO,
+            stalled: false,
+        }));
+        let params = Rc::new(RefCell::new(params));
 
         Self {
             voltage: voltage.clone(),
-            lever_stage: lever_stage.clone(),
+            state: state.clone(),
+            params: params.clone(),
             _task: spawn(async move {
+                let mut prev: Option<(Instant, Angle)> = None;
+                let mut prev_stage = LeverStage::Idle;
+                let mut stalled_since = None;
+                let mut pid = params.borrow().pid;
+
                 loop {
-                    sleep(Duration::from_millis(10)).await;
+                    sleep(PERIOD).await;
 
                     let voltage = *voltage.borrow();
-                    _ = intake.set_voltage(voltage);
+                    intake.set_voltage(voltage);
 
-                    let mut lever_stage = lever_stage.borrow_mut();
-                    let position = -rotation.position().unwrap_or_default().wrapped_half();
-                    match *lever_stage {
+                    let params = *params.borrow();
+                    let mut angle = rotation.position().unwrap_or_default().wrapped_half();
+                    if params.reversed {
+                        angle = -angle;
+                    }
+
+                    // Time since the last update, which is longer than the
+                    // period after a score pause or a slow poll
+                    let now = clock::now();
+                    let (dt, velocity) = match prev {
+                        Some((time, prev_angle)) if now > time => {
+                            let dt = now - time;
+                            (dt, (angle - prev_angle).as_degrees() / dt.as_secs_f64())
+                        }
+                        _ => (PERIOD, 0.0),
+                    };
+                    prev = Some((now, angle));
+
+                    let mut state = state.borrow_mut();
+                    state.angle = angle;
+
+                    if state.stage != prev_stage {
+                        pid = params.pid;
+                        stalled_since = None;
+                        prev_stage = state.stage;
+                    }
+
+                    let output = match state.stage {
                         LeverStage::Score(start, end) => {
-                            if position > Angle::from_degrees(120.0) {
-                                *lever_stage = LeverStage::Reset;
-                                drop(lever_stage);
-                                sleep(Duration::from_millis(50)).await;
-                            }
-
-                            let max = start.max(end);
-                            let min = start.min(end);
-                            let range = max - min;
-                            let change = position.as_degrees() * range / 120.0;
-                            let voltage = if start < end {
-                                (start + change).clamp(start, end)
-                            } else {
-                                (start - change).clamp(end, start)
-                            };
-
-                            // info!("{voltage} - {change}");
-
-                            lever.set_voltage(voltage);
+                            let progress = angle.as_radians() / params.score_angle.as_radians();
+                            params.ramp.voltage(start, end, progress)
                         }
-                        LeverStage::Reset => {
-                            if position < Angle::from_degrees(5.0) {
-                                *lever_stage = LeverStage::Idle;
-                            }
+                        LeverStage::Reset => params.reset_voltage,
+                        LeverStage::Idle => params.idle_voltage,
+                        LeverStage::Hold(target) => pid
+                            .output((target - angle).as_degrees(), dt)
+                            .clamp(-Motor::V5_MAX_VOLTAGE, Motor::V5_MAX_VOLTAGE),
+                    };
+                    lever.set_voltage(output);
 
-                            lever.set_voltage(-Motor::V5_MAX_VOLTAGE);
+                    // A lever driven hard without moving is jammed. Reset and
+                    // idle press against the hard stop, so only check while
+                    // it should be moving.
+                    let moving = match state.stage {
+                        LeverStage::Score(..) => true,
+                        LeverStage::Hold(target) => (target - angle).abs() > params.tolerance,
+                        LeverStage::Reset | LeverStage::Idle => false,
+                    };
+                    let stalling = params.stall.filter(|stall| {
+                        moving && output.abs() > stall.voltage && velocity.abs() < stall.velocity
+                    });
+                    if let Some(stall) = stalling {
+                        let since = *stalled_since.get_or_insert_with(clock::now);
+                        if clock::elapsed(since) > stall.time {
+                            warn!("Lever stalled at {:.0}°", angle.as_degrees());
+                            state.stalled = true;
+                            state.stage = LeverStage::Reset;
+                            continue;
                         }
-                        LeverStage::Idle => {
-                            lever.set_voltage(-2.0);
+                    } else {
+                        stalled_since = None;
+                    }
+
+                    let stage = state.stage;
+                    match stage {
+                        LeverStage::Score(..) if angle > params.score_angle => {
+                            state.stage = LeverStage::Reset;
+                            drop(state);
+                            // Keep pushing briefly so the ball leaves the lever
+                            sleep(params.score_pause).await;
                         }
+                        LeverStage::Reset if angle < params.reset_angle => {
+                            state.stage = LeverStage::Idle;
+                        }
+                        _ => (),
                     }
                 }
             }),
@@ -83,10 +296,206 @@ impl Lever {
     }
 
     pub fn score(&self, stage: LeverStage) {
-        self.lever_stage.replace(stage);
+        let mut state = self.state.borrow_mut();
+        state.stage = stage;
+        state.stalled = false;
+    }
+
+    /// Holds the lever at `angle` with the position controller.
+    pub fn hold(&self, angle: Angle) {
+        self.score(LeverStage::Hold(angle));
     }
 
     pub fn stage(&self) -> LeverStage {
-        *self.lever_stage.borrow()
+        self.state.borrow().stage
+    }
+
+    /// Returns the angle of the lever above its resting position.
+    pub fn angle(&self) -> Angle {
+        self.state.borrow().angle
+    }
+
+    /// Returns `true` if the lever stalled since the last command.
+    pub fn is_stalled(&self) -> bool {
+        self.state.borrow().stalled
+    }
+
+    /// Returns the lever's geometry and tuning.
+    pub fn params(&self) -> LeverParameters {
+        *self.params.borrow()
+    }
+
+    /// Sets the lever's geometry and tuning. Takes effect on the next update.
+    pub fn set_params(&self, params: LeverParameters) {
+        self.params.replace(params);
+    }
+
+    /// Waits until the lever has scored and reset to idle.
+    ///
+    /// Returns [`LeverError::Stalled`] if the lever jammed along the way.
+    pub async fn score_done(&self) -> Result<(), LeverError> {
+        loop {
+            {
+                let state = self.state.borrow();
+                if state.stalled {
+                    return Err(LeverError::Stalled);
+                }
+                if state.stage == LeverStage::Idle {
+                    return Ok(());
+                }
+            }
+
+            sleep(PERIOD).await;
+        }
+    }
+
+    /// Waits until the lever is within tolerance of its `Hold` target.
+    ///
+    /// Returns immediately if the lever is not holding, and
+    /// [`LeverError::Stalled`] if the lever jammed on the way.
+    pub async fn hold_done(&self) -> Result<(), LeverError> {
+        let tolerance = self.params().tolerance;
+
+        loop {
+            {
+                let state = self.state.borrow();
+                if state.stalled {
+                    return Err(LeverError::Stalled);
+                }
+                match state.stage {
+                    LeverStage::Hold(target) if (target - state.angle).abs() > tolerance => (),
+                    _ => return Ok(()),
+                }
+            }
+
+            sleep(PERIOD).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vexide::runtime::block_on;
+
+    use super::*;
+    use crate::hardware::mock::{MockEncoder, MockMotor};
+
+    /// Speed of the simulated lever (degrees per second per volt).
+    const SPEED: f64 = 50.0;
+
+    /// A lever on mock devices, swinging between a hard stop at 0° and an
+    /// optional jam.
+    struct TestLever {
+        lever: Lever,
+        _physics: Task<()>,
+    }
+
+    fn test_lever(jam: Option<Angle>) -> TestLever {
+        clock::use_virtual_time();
+
+        let motor = MockMotor::new();
+        let encoder = MockEncoder::new();
+        let params = LeverParameters {
+            reversed: false,
+            ..Default::default()
+        };
+        let lever = Lever::with_params(
+            MockMotor::new(),
+            MotorGroup::new(vec![motor.clone()], None),
+            encoder.clone(),
+            params,
+        );
+
+        // Turns the lever by its commanded voltage and advances the clock
+        let physics = spawn(async move {
+            let step = Duration::from_millis(1);
+            let mut angle = 0.0;
+            loop {
+                angle += motor.state().voltage * SPEED * step.as_secs_f64();
+                angle = angle.max(0.0);
+                if let Some(jam) = jam {
+                    angle = angle.min(jam.as_degrees());
+                }
+                encoder.set_position(Some(Angle::from_degrees(angle)));
+
+                clock::advance(step);
+                clock::yield_now().await;
+            }
+        });
+
+        TestLever {
+            lever,
+            _physics: physics,
+        }
+    }
+
+    #[test]
+    fn stall_resets_lever() {
+        block_on(async {
+            let TestLever { lever, _physics } = test_lever(Some(Angle::from_degrees(40.0)));
+
+            lever.score(LeverStage::Score(12.0, 12.0));
+            let start = clock::now();
+            assert_eq!(lever.score_done().await, Err(LeverError::Stalled));
+
+            // Detected once the lever has been stuck for the stall time
+            let stall = StallParameters::default().time;
+            assert!(clock::elapsed(start) > stall);
+            assert!(clock::elapsed(start) < stall + Duration::from_millis(200));
+            assert!(lever.is_stalled());
+            assert_eq!(lever.stage(), LeverStage::Reset);
+
+            sleep(Duration::from_millis(500)).await;
+            assert_eq!(lever.stage(), LeverStage::Idle);
+            assert!(lever.angle() < lever.params().reset_angle);
+        });
+        clock::use_real_time();
+    }
+
+    #[test]
+    fn score_finishes_without_jam() {
+        block_on(async {
+            let TestLever { lever, _physics } = test_lever(None);
+
+            lever.score(LeverStage::Score(12.0, 12.0));
+            assert_eq!(lever.score_done().await, Ok(()));
+            assert!(!lever.is_stalled());
+        });
+        clock::use_real_time();
+    }
+
+    #[test]
+    fn hold_converges_on_target() {
+        block_on(async {
+            let TestLever { lever, _physics } = test_lever(None);
+            let target = Angle::from_degrees(60.0);
+
+            lever.hold(target);
+            assert_eq!(lever.hold_done().await, Ok(()));
+
+            // Stays there once reached
+            sleep(Duration::from_millis(500)).await;
+            let error = (target - lever.angle()).abs();
+            assert!(
+                error <= lever.params().tolerance,
+                "{} deg off",
+                error.as_degrees()
+            );
+            assert_eq!(lever.stage(), LeverStage::Hold(target));
+        });
+        clock::use_real_time();
+    }
+
+    #[test]
+    fn ramps_from_start_to_end_voltage() {
+        let linear = LeverRamp::Linear;
+        assert_eq!(linear.voltage(8.0, 3.0, -0.2), 8.0);
+        assert_eq!(linear.voltage(8.0, 3.0, 0.5), 5.5);
+        assert_eq!(linear.voltage(6.0, 12.0, 1.5), 12.0);
+
+        assert_eq!(LeverRamp::Constant.voltage(6.0, 12.0, 0.5), 6.0);
+
+        let eased = LeverRamp::Custom(|progress| progress * progress);
+        assert_eq!(eased.voltage(4.0, 12.0, 0.5), 6.0);
     }
 }
@@ -1,6 +1,6 @@
 use std::time::{Duration, Instant};
 
-use vexide::time::sleep;
+use crate::clock::{self, sleep};
 
 /// Apply a polynomial acceleration curve
 ///
@@ -32,9 +32,115 @@ pub async fn wait_with_timeout<F>(timeout: Duration, mut f: F)
 where
     F: FnMut() -> bool,
 {
-    let start = Instant::now();
+    let start = clock::now();
 
-    while f() || start.elapsed() < timeout {
+    while f() || clock::elapsed(start) < timeout {
         sleep(Duration::from_millis(10)).await;
     }
 }
+
+/// Solves the linear system `a * x = b` using Gaussian elimination with
+/// partial pivoting.
+///
+/// Returns `None` if the system is singular or nearly singular.
+pub fn solve_linear<const N: usize>(mut a: [[f64; N]; N], mut b: [f64; N]) -> Option<[f64; N]> {
+    for col in 0..N {
+        // Swap in the row with the largest pivot to keep the elimination stable
+        let pivot = (col..N).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivot][col].abs() < 1e-12 {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+
+        for row in (col + 1)..N {
+            let factor = a[row][col] / a[col][col];
+            for k in col..N {
+                a[row][k] -= factor * a[col][k];
+            }
+            b[row] -= factor * b[col];
+        }
+    }
+
+    // Back substitution
+    let mut x = [0.0; N];
+    for row in (0..N).rev() {
+        let sum: f64 = ((row + 1)..N).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (b[row] - sum) / a[row][row];
+    }
+
+    Some(x)
+}
+
+/// Timing statistics of a [`FixedRate`] loop.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LoopStats {
+    /// Number of completed iterations.
+    pub iterations: u64,
+
+    /// Number of iterations that finished after their deadline.
+    pub overruns: u64,
+
+    /// Largest amount an iteration finished past its deadline.
+    pub worst_overrun: Duration,
+}
+
+/// Runs a loop at a fixed rate.
+///
+/// Unlike sleeping for a fixed duration after each iteration, the time spent
+/// doing work is accounted for, so the loop does not drift slower than
+/// `period`. An iteration that runs past its deadline is counted as an
+/// overrun and the schedule restarts from the current time instead of
+/// trying to catch up.
+pub struct FixedRate {
+    period: Duration,
+    deadline: Instant,
+    stats: LoopStats,
+}
+
+impl FixedRate {
+    /// Creates a loop that runs once every `period`, starting now.
+    pub fn new(period: Duration) -> Self {
+        Self {
+            period,
+            deadline: clock::now() + period,
+            stats: LoopStats::default(),
+        }
+    }
+
+    /// Returns the target time between iterations.
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    /// Sets the target time between iterations, taking effect from the
+    /// next deadline.
+    pub fn set_period(&mut self, period: Duration) -> &mut Self {
+        self.period = period;
+        self
+    }
+
+    /// Returns the timing statistics of the loop so far.
+    pub fn stats(&self) -> LoopStats {
+        self.stats
+    }
+
+    /// Waits until the next deadline.
+    pub async fn wait(&mut self) {
+        let now = clock::now();
+        self.stats.iterations += 1;
+
+        if now > self.deadline {
+            let overrun = now - self.deadline;
+            self.stats.overruns += 1;
+            self.stats.worst_overrun = self.stats.worst_overrun.max(overrun);
+
+            // Skip the missed deadlines rather than running back to back
+            self.deadline = now;
+        } else {
+            sleep(self.deadline - now).await;
+        }
+
+        self.deadline += self.period;
+    }
+}
@@ -53,11 +53,11 @@ impl Robot {
         );
 
         let dt = &mut self.drivetrain;
-        _ = self.lift.set_high();
-        let mut target = Vec2::new(115.0, self.pose.borrow().y);
+        self.lift.extend();
+        let mut target = Vec2::new(115.0, self.pose.snapshot().y);
         self.lever.set_intake(Motor::V5_MAX_VOLTAGE);
         _ = move_to.move_to_point(dt, target).await;
-        _ = self.match_loader.set_high();
+        self.match_loader.extend();
         // _ = turn.turn_to_point(dt, Vec2::new(115.0, 13.0), true).await;
         _ = turn.turn_to(dt, Angle::QUARTER_TURN).await;
 
@@ -66,7 +66,7 @@ impl Robot {
         dt.set_arcade(-0.6, 0.0);
         sleep(Duration::from_millis(400)).await;
         _ = move_to.speed(1.0).tolerance(1.0).min_velocity(Some(1.0)).move_to_point(dt, Vec2::new(117.5, 41.5)).await;
-        _ = self.duck_bill.set_high();
+        self.duck_bill.extend();
         self.lever.score(LeverStage::Score(6.0, 6.0));
         // sleep(Duration::from_millis(300)).await;
         // wait_with_timeout(Duration::from_millis(700), || {
@@ -76,12 +76,12 @@ impl Robot {
 
         self.lever.score(LeverStage::Reset);
         _ = move_to.min_velocity(Some(0.5)).speed(0.8).move_to_point(dt, Vec2::new(117.0 ,12.0)).await;
-        _ = self.duck_bill.set_low();
+        self.duck_bill.retract();
         dt.set_arcade(-0.25, 0.0);
         sleep(Duration::from_millis(1000)).await;
-        target = Vec2::new(self.pose.borrow().x, 20.0);
+        target = Vec2::new(self.pose.snapshot().x, 20.0);
         _ = move_to.move_to_point(dt,target).await;
-        _ = self.match_loader.set_low();
+        self.match_loader.retract();
         _ = turn.turn_to(dt, Angle::from_degrees(135.0)).await;
         
 
@@ -89,49 +89,49 @@ impl Robot {
 
         sleep(Duration::from_millis(1000)).await;
 
-         _ = self.match_loader.set_high();
+         self.match_loader.extend();
         // _ = turn.tolerance(Angle::from_degrees(1.0)).turn_to_point(dt, Vec2::new(117.0, 13.0), true).await;
         _ = turn.turn_to(dt, Angle::QUARTER_TURN).await;
         self.lever.set_intake(Motor::V5_MAX_VOLTAGE);
         _ = move_to.min_velocity(Some(0.5)).speed(0.7).move_to_point(dt, Vec2::new(117.0 ,12.5)).await;
-        _ = self.duck_bill.set_low();
+        self.duck_bill.retract();
 
         dt.set_arcade(-0.25, 0.0);
 
 
         sleep(Duration::from_millis(1500)).await;
-        target = Vec2::new(self.pose.borrow().x, 20.0);
+        target = Vec2::new(self.pose.snapshot().x, 20.0);
         _ = move_to.move_to_point(dt, target).await;
         _ = turn.turn_to_point(dt, Vec2::new(80.0,56.0), true).await;
-        _ = self.match_loader.set_low();
+        self.match_loader.retract();
         _ = linear.speed(0.5).drive_to_point(dt, Vec2::new(82.0, 60.0), true).await;
         // _ = move_to.move_to_point(dt, Vec2::new(86.0, 55.0)).await;
         self.lever.set_intake(-Motor::V5_MAX_VOLTAGE/2.25);
         sleep(Duration::from_millis(1500)).await;
         _ = move_to.speed(0.5).timeout(Duration::from_millis(1500)).move_to_point(dt, Vec2::new(117.0,24.0)).await;
-        _ = self.match_loader.set_high();
+        self.match_loader.extend();
         _ = turn.turn_to(dt, Angle::QUARTER_TURN).await;
         self.lever.set_intake(Motor::V5_MAX_VOLTAGE);
         _ = move_to.min_velocity(Some(0.5)).speed(0.7).move_to_point(dt, Vec2::new(117.0 ,12.5)).await;
-        _ = self.duck_bill.set_low();
+        self.duck_bill.retract();
 
         dt.set_arcade(-0.25, 0.0);
         sleep(Duration::from_millis(1600)).await;
 
-        _ = self.match_loader.set_low();
+        self.match_loader.retract();
 
         _ = move_to.move_to_point(dt, Vec2::new(87.0, 50.0)).await;
         _ = turn.turn_to(dt, Angle::ZERO).await;
-        target = Vec2::new(54.5, self.pose.borrow().y);
+        target = Vec2::new(54.5, self.pose.snapshot().y);
         _ = move_to.move_to_point(dt, target).await;
         _ = turn.turn_to(dt, Angle::from_degrees(45.0)).await;
-        _ = self.lift.set_low();
+        self.lift.retract();
         _ = linear.drive_distance(dt, 10.0).await;
 
         self.lever.score(LeverStage::Score(4.0, 2.5));
 
         _ = sleep(Duration::from_millis(3000)).await;
-        _ = self.duck_bill.set_low();
+        self.duck_bill.retract();
 
 
 
@@ -171,11 +171,11 @@ impl Robot {
         );
 
         let dt = &mut self.drivetrain;
-        _ = self.lift.set_high();
-        let mut target = Vec2::new(115.0, self.pose.borrow().y);
+        self.lift.extend();
+        let mut target = Vec2::new(115.0, self.pose.snapshot().y);
         self.lever.set_intake(Motor::V5_MAX_VOLTAGE);
         _ = move_to.move_to_point(dt, target).await;
-        _ = self.match_loader.set_high();
+        self.match_loader.extend();
         // _ = turn.turn_to_point(dt, Vec2::new(115.0, 13.0), true).await;
         _ = turn.turn_to(dt, Angle::QUARTER_TURN).await;
 
@@ -184,7 +184,7 @@ impl Robot {
         dt.set_arcade(-0.6, 0.0);
         sleep(Duration::from_millis(400)).await;
         _ = move_to.speed(1.0).tolerance(1.0).min_velocity(Some(1.0)).move_to_point(dt, Vec2::new(117.5, 41.5)).await;
-        _ = self.duck_bill.set_high();
+        self.duck_bill.extend();
         self.lever.score(LeverStage::Score(6.0, 6.0));
         // sleep(Duration::from_millis(300)).await;
         // wait_with_timeout(Duration::from_millis(700), || {
@@ -194,12 +194,12 @@ impl Robot {
 
         self.lever.score(LeverStage::Reset);
         _ = move_to.min_velocity(Some(0.5)).speed(0.8).move_to_point(dt, Vec2::new(117.0 ,12.0)).await;
-        _ = self.duck_bill.set_low();
+        self.duck_bill.retract();
         dt.set_arcade(-0.25, 0.0);
         sleep(Duration::from_millis(1000)).await;
-        target = Vec2::new(self.pose.borrow().x, 20.0);
+        target = Vec2::new(self.pose.snapshot().x, 20.0);
         _ = move_to.move_to_point(dt,target).await;
-        _ = self.match_loader.set_low();
+        self.match_loader.retract();
         _ = turn.turn_to(dt, Angle::from_degrees(135.0)).await;
         
 
@@ -207,21 +207,21 @@ impl Robot {
 
         sleep(Duration::from_millis(1000)).await;
 
-         _ = self.match_loader.set_high();
+         self.match_loader.extend();
         // _ = turn.tolerance(Angle::from_degrees(1.0)).turn_to_point(dt, Vec2::new(117.0, 13.0), true).await;
         _ = turn.turn_to(dt, Angle::QUARTER_TURN).await;
         self.lever.set_intake(Motor::V5_MAX_VOLTAGE);
         _ = move_to.min_velocity(Some(0.5)).speed(0.7).move_to_point(dt, Vec2::new(117.0 ,12.5)).await;
-        _ = self.duck_bill.set_low();
+        self.duck_bill.retract();
 
         dt.set_arcade(-0.25, 0.0);
 
 
         sleep(Duration::from_millis(1500)).await;
-        target = Vec2::new(self.pose.borrow().x, 20.0);
+        target = Vec2::new(self.pose.snapshot().x, 20.0);
         _ = move_to.move_to_point(dt, target).await;
         _ = turn.turn_to_point(dt, Vec2::new(80.0,56.0), true).await;
-        _ = self.match_loader.set_low();
+        self.match_loader.retract();
         _ = linear.speed(0.5).drive_to_point(dt, Vec2::new(82.0, 60.0), true).await;
         // _ = move_to.move_to_point(dt, Vec2::new(86.0, 55.0)).await;
         self.lever.set_intake(-Motor::V5_MAX_VOLTAGE/2.25);
@@ -229,13 +229,13 @@ impl Robot {
 
         _ = move_to.speed(0.5).timeout(Duration::from_millis(1000)).move_to_point(dt, Vec2::new(106.0,30.0)).await;
         _ = turn.tolerance(Angle::from_degrees(2.0)).turn_to(dt, Angle::QUARTER_TURN).await;
-        _ = self.wing.set_high();
+        self.wing.extend();
         _ = move_to.speed(1.0).move_to_point(dt, Vec2::new(107.0,64.0)).await; 
         dt.brake(BrakeMode::Hold);
-        _ = self.duck_bill.set_low();
+        self.duck_bill.retract();
         info!("Time: {}", start.elapsed().as_millis());
         _ = sleep_until(start+Duration::from_secs_f64(29.75)).await;
-        _ = self.wing.set_low();
+        self.wing.retract();
 
         
         
@@ -275,11 +275,11 @@ impl Robot {
         );
 
         let dt = &mut self.drivetrain;
-        _ = self.lift.set_high();
-        let mut target = Vec2::new(115.0, self.pose.borrow().y);
+        self.lift.extend();
+        let mut target = Vec2::new(115.0, self.pose.snapshot().y);
         self.lever.set_intake(Motor::V5_MAX_VOLTAGE);
         _ = move_to.move_to_point(dt, target).await;
-        _ = self.match_loader.set_high();
+        self.match_loader.extend();
         // _ = turn.turn_to_point(dt, Vec2::new(115.0, 13.0), true).await;
         _ = turn.turn_to(dt, Angle::QUARTER_TURN).await;
 
@@ -287,7 +287,7 @@ impl Robot {
         dt.set_arcade(-0.6, 0.0);
         sleep(Duration::from_millis(400)).await;
         _ = move_to.speed(1.0).tolerance(1.0).min_velocity(Some(1.0)).move_to_point(dt, Vec2::new(117.5, 41.5)).await;
-        _ = self.duck_bill.set_high();
+        self.duck_bill.extend();
         self.lever.score(LeverStage::Score(6.0, 6.0));
         // sleep(Duration::from_millis(300)).await;
         wait_with_timeout(Duration::from_millis(700), || {
@@ -296,12 +296,12 @@ impl Robot {
 
 
         _ = move_to.min_velocity(Some(0.5)).speed(0.8).move_to_point(dt, Vec2::new(117.0 ,12.0)).await;
-        _ = self.duck_bill.set_low();
+        self.duck_bill.retract();
         dt.set_arcade(-0.25, 0.0);
         sleep(Duration::from_millis(1000)).await;
-        target = Vec2::new(self.pose.borrow().x, 20.0);
+        target = Vec2::new(self.pose.snapshot().x, 20.0);
         _ = move_to.move_to_point(dt,target).await;
-        _ = self.match_loader.set_low();
+        self.match_loader.retract();
         _ = turn.turn_to(dt, Angle::from_degrees(135.0)).await;
         
 
@@ -310,12 +310,12 @@ impl Robot {
         sleep(Duration::from_millis(1000)).await;
 
 
-        _ = self.match_loader.set_high();
+        self.match_loader.extend();
         _ = turn.tolerance(Angle::from_degrees(1.0)).turn_to_point(dt, Vec2::new(117.0, 13.0), true).await;
         self.lever.set_intake(Motor::V5_MAX_VOLTAGE);
 
         _ = move_to.min_velocity(Some(0.5)).speed(0.7).move_to_point(dt, Vec2::new(117.5 ,13.0)).await;
-        _ = self.duck_bill.set_low();
+        self.duck_bill.retract();
 
         dt.set_arcade(-0.25, 0.0);
 
@@ -324,22 +324,22 @@ impl Robot {
         self.lever.set_intake(Motor::V5_MAX_VOLTAGE);
         _ = move_to.timeout(Duration::from_millis(1000)).speed(1.0).tolerance(2.0).min_velocity(Some(1.0)).move_to_point(dt, Vec2::new(117.0, 42.0)).await;
         dt.set_arcade(2.0, 0.0);
-        _ = self.duck_bill.set_high();
+        self.duck_bill.extend();
         self.lever.score(LeverStage::Score(6.0, 4.0));
         wait_with_timeout(Duration::from_millis(1000), || {
              !matches!(self.lever.stage(), LeverStage::Reset)
         }).await;
         _ = move_to.speed(0.4).move_to_point(dt, Vec2::new(109.0,33.0)).await;
         _ = turn.turn_to(dt, Angle::QUARTER_TURN).await;
-        _ = self.wing.set_high();
+        self.wing.extend();
         _ = move_to.speed(0.5).move_to_point(dt, Vec2::new(107.0,64.0)).await; 
         dt.brake(BrakeMode::Hold);
-        _ = self.duck_bill.set_low();
-        _ = self.match_loader.set_low();
+        self.duck_bill.retract();
+        self.match_loader.retract();
         info!("Time: {}", start.elapsed().as_millis());
 
         _ = sleep_until(start+Duration::from_secs_f64(29.75)).await;
-        _ = self.wing.set_low();
+        self.wing.retract();
 
 
         
@@ -378,19 +378,19 @@ impl Robot {
         );
 
         let dt = &mut self.drivetrain;
-        _ = self.lift.set_high();
-        let mut target = Vec2::new(115.0, self.pose.borrow().y);
+        self.lift.extend();
+        let mut target = Vec2::new(115.0, self.pose.snapshot().y);
         self.lever.set_intake(Motor::V5_MAX_VOLTAGE);
         _ = move_to.speed(1.0).move_to_point(dt, target).await;
-        _ = self.match_loader.set_high();
+        self.match_loader.extend();
         // _ = turn.timeout(Duration::from_millis(750)).turn_to_point(dt, Vec2::new(115.0, 13.0), true).await;
         _ = turn.timeout(Duration::from_millis(750)).turn_to(dt, Angle::QUARTER_TURN).await;
-        let target = Vec2::new(self.pose.borrow().x-0.75, 12.0);
+        let target = Vec2::new(self.pose.snapshot().x-0.75, 12.0);
         _ = move_to.min_velocity(Some(0.5)).speed(0.8).move_to_point(dt, target).await;
         dt.set_arcade(-0.5, 0.0);
         sleep(Duration::from_millis(400)).await;
         _ = move_to.speed(1.0).tolerance(1.0).min_velocity(Some(0.5)).move_to_point(dt, Vec2::new(116.0, 42.0)).await;
-        _ = self.duck_bill.set_high();
+        self.duck_bill.extend();
         self.lever.score(LeverStage::Score(8.0, 8.0));
         // sleep(Duration::from_millis(300)).await;
         wait_with_timeout(Duration::from_millis(300), || {
@@ -398,17 +398,17 @@ impl Robot {
         }).await;
         _ = move_to.speed(0.7).timeout(Duration::from_millis(750)).move_to_point(dt, Vec2::new(111.0,33.0)).await;
         _ = turn.tolerance(Angle::from_degrees(2.0)).turn_to(dt, Angle::QUARTER_TURN).await;
-        _ = self.wing.set_high();
+        self.wing.extend();
         _ = move_to.speed(1.2).move_to_point(dt, Vec2::new(107.0,64.0)).await; 
         dt.brake(BrakeMode::Hold);
         info!("Time: {}", start.elapsed().as_millis());
 
-        _ = self.duck_bill.set_low();
-        _ = self.match_loader.set_low();
+        self.duck_bill.retract();
+        self.match_loader.retract();
         
 
         _ = sleep_until(start+Duration::from_secs_f64(29.75)).await;
-        _ = self.wing.set_low();
+        self.wing.retract();
         
 
     }
@@ -447,23 +447,23 @@ impl Robot {
         );
 
         let dt = &mut self.drivetrain;
-        _ = self.lift.set_high();
-        let mut target = Vec2::new(115.0, self.pose.borrow().y);
+        self.lift.extend();
+        let mut target = Vec2::new(115.0, self.pose.snapshot().y);
         self.lever.set_intake(Motor::V5_MAX_VOLTAGE);
         _ = linear.drive_to_point(dt, target,true).await;
-        _ = self.match_loader.set_high();
+        self.match_loader.extend();
         _ = turn.turn_to_point(dt, Vec2::new(115.0, 13.0), true).await;
 
         _ = move_to.min_velocity(Some(1.0)).timeout(Duration::from_millis(1000)).speed(0.7).move_to_point(dt, Vec2::new(117.0 ,12.5)).await;
         dt.set_arcade(-0.25, 0.0);
         sleep(Duration::from_millis(350)).await;
-        target = Vec2::new(self.pose.borrow().x, 24.0);
+        target = Vec2::new(self.pose.snapshot().x, 24.0);
 
 
         _ = move_to.move_to_point(dt, target).await;
 
         _ = turn.turn_to_point(dt, Vec2::new(80.0,56.0), true).await;
-        _ = self.match_loader.set_low();
+        self.match_loader.retract();
 
 
         _ = linear.speed(0.5).drive_to_point(dt, Vec2::new(86.0, 60.0), true).await;
@@ -473,7 +473,7 @@ impl Robot {
         self.lever.set_intake(-Motor::V5_MAX_VOLTAGE/2.5);
         sleep(Duration::from_millis(1500)).await;
         _ = move_to.speed(1.0).tolerance(0.5).timeout(Duration::from_millis(1500)).move_to_point(dt, Vec2::new(119.0, 20.0)).await;
-        _ = self.match_loader.set_high();
+        self.match_loader.extend();
         _ = turn.turn_to(dt, Angle::QUARTER_TURN).await;
 
         self.lever.set_intake(Motor::V5_MAX_VOLTAGE);
@@ -483,7 +483,7 @@ impl Robot {
         sleep(Duration::from_millis(1000)).await;
 
         _ = move_to.move_to_point(dt, Vec2::new(117.0, 24.0)).await;
-        _ = self.match_loader.set_low();
+        self.match_loader.retract();
         _ = turn.turn_to(dt, Angle::from_degrees(135.0)).await;
         
 
@@ -492,14 +492,14 @@ impl Robot {
         sleep(Duration::from_millis(1000)).await;
 
 
-        _ = self.match_loader.set_high();
+        self.match_loader.extend();
         // _ = turn.speed(2.0).timeout(Duration::from_millis(1000)).turn_to_point(dt, Vec2::new(117.0, 13.0), true).await;
         _ = turn.turn_to(dt, Angle::QUARTER_TURN).await;
         self.lever.set_intake(Motor::V5_MAX_VOLTAGE);
         
 
         _ = move_to.min_velocity(Some(0.5)).speed(0.7).move_to_point(dt, Vec2::new(116.0 ,12.0)).await;
-        _ = self.duck_bill.set_low();
+        self.duck_bill.retract();
 
         dt.set_arcade(-0.25, 0.0);
 
@@ -508,21 +508,21 @@ impl Robot {
         _ = move_to.speed(1.0).tolerance(2.0).min_velocity(Some(0.5)).move_to_point(dt, Vec2::new(117.5, 42.5)).await;
         dt.set_arcade(2.0, 0.0);
         self.lever.set_intake(Motor::V5_MAX_VOLTAGE);
-        _ = self.duck_bill.set_high();
+        self.duck_bill.extend();
         self.lever.score(LeverStage::Score(8.0, 6.0));
 
         sleep(Duration::from_millis(1200)).await;  
         _ = move_to.speed(0.4).move_to_point(dt, Vec2::new(109.0,33.0)).await;
         _ = turn.turn_to(dt, Angle::QUARTER_TURN).await;
-        _ = self.wing.set_high();
+        self.wing.extend();
         _ = move_to.speed(1.0).move_to_point(dt, Vec2::new(107.0,64.0)).await; 
         dt.brake(BrakeMode::Hold);
-        _ = self.duck_bill.set_low();
-        _ = self.match_loader.set_low();
+        self.duck_bill.retract();
+        self.match_loader.retract();
         info!("Time: {}", start.elapsed().as_millis());
 
         _ = sleep_until(start+Duration::from_secs_f64(29.75)).await;
-        _ = self.wing.set_low(); 
+        self.wing.retract(); 
 
 
 
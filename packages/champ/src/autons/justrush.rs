@@ -58,25 +58,25 @@ impl Robot {
         let dt = &mut self.drivetrain;
 
         // Drive to and collect matchload balls
-        let mut target = Vec2::new(24.5, self.pose.borrow().y);
+        let mut target = Vec2::new(24.5, self.pose.snapshot().y);
         _ = move_to.timeout(Duration::from_millis(900)).move_to_point(dt, target).await;
-        _ = self.lift.set_high();
-        _ = self.match_loader.set_high();
+        self.lift.extend();
+        self.match_loader.extend();
         _ = turn.speed(0.8).timeout(Duration::from_millis(500)).turn_to(dt, Angle::from_degrees(-90.0)).await;
         self.lever.set_intake(Motor::V5_MAX_VOLTAGE);
         _ = linear.speed(0.9).timeout(Duration::from_millis(850)).drive_distance(dt, 13.0).await;
 
         // Score on long goal
         _ = move_to.speed(1.0).timeout(Duration::from_millis(1000)).move_to_point(dt, Vec2::new(23.5, 42.25)).await;
-        _ = self.duck_bill.set_high();
+        self.duck_bill.extend();
         self.lever.score(LeverStage::Score(5.0, 6.0));
         sleep(Duration::from_millis(500)).await;
 
         // Wing
         _ = move_to.speed(0.5).move_to_point(dt, Vec2::new(33.5, 33.0)).await;
         _ = turn.tolerance(Angle::from_degrees(1.0)).speed(0.6).turn_to(dt, Angle::QUARTER_TURN).await;
-        _ = self.wing.toggle();
-        _ = self.match_loader.set_low();
+        self.wing.toggle();
+        self.match_loader.retract();
         _ = move_to.speed(0.5).move_to_point(dt, Vec2::new(33.5, 64.0)).await;
         _ = linear.min_velocity(5.0).speed(0.7).drive_distance(dt, 8.0).await;
         dt.brake(BrakeMode::Hold);
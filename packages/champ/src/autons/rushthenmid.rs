@@ -55,63 +55,63 @@ impl Robot {
         let dt = &mut self.drivetrain;
 
         // Drive to and collect matchload balls
-        let mut target = Vec2::new(24.5, self.pose.borrow().y);
+        let mut target = Vec2::new(24.5, self.pose.snapshot().y);
         _ = move_to.timeout(Duration::from_millis(1000)).move_to_point(dt, target).await;
-        _ = self.lift.set_high();
-        _ = self.match_loader.set_high();
+        self.lift.extend();
+        self.match_loader.extend();
         _ = turn.speed(0.6).timeout(Duration::from_millis(500)).turn_to(dt, Angle::from_degrees(-90.0)).await;
         self.lever.set_intake(Motor::V5_MAX_VOLTAGE);
         _ = linear.speed(0.8).timeout(Duration::from_millis(885)).drive_distance(dt, 12.0).await;
 
         // Score on long goal
         _ = move_to.timeout(Duration::from_millis(1000)).move_to_point(dt, Vec2::new(23.5, 42.25)).await;
-        _ = self.duck_bill.set_high();
+        self.duck_bill.extend();
         self.lever.score(LeverStage::Score(5.0, 8.0));
         sleep(Duration::from_millis(500)).await;
 
         // Return to matchloader
         _ = linear.timeout(Duration::from_millis(500)).drive_to_point(dt, Vec2::new(24.0, 24.0), false).await;
-        _ = self.duck_bill.set_low();
-        _ = self.lift.set_high();
+        self.duck_bill.retract();
+        self.lift.extend();
         _ = turn.speed(0.8).timeout(Duration::from_millis(500)).turn_to_point(dt, Vec2::new(24.5, 0.0), false).await;
         self.lever.set_intake(Motor::V5_MAX_VOLTAGE);
         _ = linear.speed(0.4).timeout(Duration::from_millis(1500)).drive_distance(dt, 23.0).await;
 
         // Spit opposing color balls into wall
         _ = linear.speed(1.0).drive_distance(dt, -12.0).await;
-        _ = self.match_loader.set_low();
+        self.match_loader.retract();
         _ = turn.tolerance(Angle::from_degrees(5.0)).speed(0.6).turn_to(dt, Angle::from_degrees(-135.0)).await;
         self.lever.set_intake(-Motor::V5_MAX_VOLTAGE);
         sleep(Duration::from_millis(750)).await;
 
         // Return to matchloader
-        _ = self.match_loader.set_high();
+        self.match_loader.extend();
         _ = turn.timeout(Duration::from_millis(500)).tolerance(Angle::from_degrees(5.0)).speed(1.0).turn_to_point(dt, Vec2::new(24.5, 0.0), false).await;
         self.lever.set_intake(Motor::V5_MAX_VOLTAGE);
         _ = linear.speed(0.4).timeout(Duration::from_millis(1500)).drive_distance(dt, 22.0).await;
         
         // Drive to mid goal
-        _ = self.lift.set_low();
+        self.lift.retract();
         _ = linear.speed(0.7).timeout(Duration::from_millis(1100)).drive_to_point(dt, Vec2::new(24.0, 24.0), true).await;
         _ = turn.turn_to(dt, Angle::from_degrees(225.0)).await;
         _ = move_to.speed(0.60).timeout(Duration::from_millis(2000)).move_to_point(dt, Vec2::new(57.0, 57.0)).await;
 
         // Score mid
-        _ = self.duck_bill.set_high();
+        self.duck_bill.extend();
         self.lever.score(LeverStage::Score(2.0, 5.75));
         sleep(Duration::from_millis(1000)).await;
 
         // Wing
         _ = move_to.speed(0.35).move_to_point(dt, Vec2::new(32.5, 28.0)).await;
-        _ = self.lift.set_high();
-        _ = self.duck_bill.set_low();
+        self.lift.extend();
+        self.duck_bill.retract();
         _ = turn.tolerance(Angle::from_degrees(1.0)).speed(0.6).turn_to(dt, Angle::from_degrees(90.0)).await;
-        _ = self.wing.toggle();
+        self.wing.toggle();
         _ = move_to.speed(1.0).move_to_point(dt, Vec2::new(32.3, 64.0)).await;
         dt.brake(BrakeMode::Hold);
 
         sleep_until(timer + Duration::from_secs(29)).await;
-        _ = self.wing.set_low();
+        self.wing.retract();
     }
 }
  
\ No newline at end of file
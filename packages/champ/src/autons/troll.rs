@@ -62,7 +62,7 @@ impl Robot {
         _ = move_to.speed(0.6).timeout(Duration::from_millis(2500)).move_to_point(dt, Vec2::new(54.0, 83.0)).await;
         _ = turn.timeout(Duration::from_millis(500)).turn_to_point(dt, Vec2::new(72.0, 72.0), false).await;
         _ = linear.timeout(Duration::from_millis(750)).drive_distance(dt, 6.0).await;
-        _ = self.lift.set_high();
+        self.lift.extend();
     
         // Wait.
         dt.brake(BrakeMode::Hold);
@@ -75,26 +75,26 @@ impl Robot {
         _ = turn.turn_to(dt, Angle::from_degrees(240.0)).await;
         self.lever.set_intake(Motor::V5_MAX_VOLTAGE);
         _ = move_to.speed(0.6).timeout(Duration::from_millis(2500)).move_to_point(dt, Vec2::new(26.5,24.5)).await;
-        _ = self.lift.set_high();
-        _ = self.match_loader.set_high();
+        self.lift.extend();
+        self.match_loader.extend();
         _ = turn.speed(0.6).timeout(Duration::from_millis(500)).turn_to(dt, Angle::from_degrees(-90.0)).await;
         self.lever.set_intake(Motor::V5_MAX_VOLTAGE);
         _ = linear.speed(0.9).timeout(Duration::from_millis(860)).drive_distance(dt, 16.0).await;
 
         // Score on long goal
         _ = move_to.speed(0.8).timeout(Duration::from_millis(1000)).move_to_point(dt, Vec2::new(23.0, 42.0)).await;
-        _ = self.match_loader.set_low();
-        _ = self.duck_bill.set_high();
+        self.match_loader.retract();
+        self.duck_bill.extend();
         self.lever.score(LeverStage::Score(5.0, 8.0));
         sleep(Duration::from_millis(500)).await;
 
         // Wing
         _ = move_to.speed(0.3).move_to_point(dt, Vec2::new(34.5, 28.0)).await;
         _ = turn.tolerance(Angle::from_degrees(1.0)).speed(0.6).turn_to(dt, Angle::from_degrees(90.0)).await;
-        _ = self.wing.toggle();
+        self.wing.toggle();
         _ = move_to.speed(0.9).move_to_point(dt, Vec2::new(32.5, 60.0)).await;
         dt.brake(BrakeMode::Hold);
         sleep_until(timer + Duration::from_secs(29)).await;
-        _ = self.wing.set_low();    }
+        self.wing.retract();    }
 }
  
\ No newline at end of file
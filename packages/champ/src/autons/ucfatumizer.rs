@@ -56,53 +56,53 @@ impl Robot {
         let dt = &mut self.drivetrain;
 
         // Drive to and collect matchload balls
-        let mut target = Vec2::new(24.5, self.pose.borrow().y);
+        let mut target = Vec2::new(24.5, self.pose.snapshot().y);
         _ = move_to.timeout(Duration::from_millis(1000)).move_to_point(dt, target).await;
-        _ = self.lift.set_high();
-        _ = self.match_loader.set_high();
+        self.lift.extend();
+        self.match_loader.extend();
         _ = turn.speed(0.6).timeout(Duration::from_millis(500)).turn_to(dt, Angle::from_degrees(-90.0)).await;
         self.lever.set_intake(Motor::V5_MAX_VOLTAGE);
         _ = linear.speed(0.8).timeout(Duration::from_millis(850)).drive_distance(dt, 13.0).await;
 
         // Score on long goal
         _ = move_to.timeout(Duration::from_millis(1000)).move_to_point(dt, Vec2::new(23.5, 42.25)).await;
-        _ = self.duck_bill.set_high();
+        self.duck_bill.extend();
         self.lever.score(LeverStage::Score(5.0, 8.0));
         sleep(Duration::from_millis(500)).await;
 
         // Return to matchloader
         self.lever.score(LeverStage::Reset);
         _ = linear.timeout(Duration::from_millis(500)).drive_to_point(dt, Vec2::new(24.0, 24.0), false).await;
-        _ = self.duck_bill.set_low();
-        _ = self.lift.set_high();
+        self.duck_bill.retract();
+        self.lift.extend();
         _ = turn.speed(0.8).timeout(Duration::from_millis(500)).turn_to_point(dt, Vec2::new(24.5, 0.0), false).await;
         self.lever.set_intake(Motor::V5_MAX_VOLTAGE);
         _ = linear.speed(0.4).timeout(Duration::from_millis(1500)).drive_distance(dt, 20.0).await;
 
         // Spit opposing color balls into wall
         _ = linear.speed(1.0).drive_distance(dt, -12.0).await;
-        _ = self.match_loader.set_low();
+        self.match_loader.retract();
         _ = turn.tolerance(Angle::from_degrees(5.0)).speed(0.6).turn_to(dt, Angle::from_degrees(-135.0)).await;
         self.lever.set_intake(-Motor::V5_MAX_VOLTAGE);
         sleep(Duration::from_millis(750)).await;
 
         // Return to matchloader
-        _ = self.match_loader.set_high();
+        self.match_loader.extend();
         _ = turn.timeout(Duration::from_millis(500)).tolerance(Angle::from_degrees(5.0)).speed(1.0).turn_to_point(dt, Vec2::new(24.5, 0.0), false).await;
         self.lever.set_intake(Motor::V5_MAX_VOLTAGE);
         _ = linear.speed(0.4).timeout(Duration::from_millis(2500)).drive_distance(dt, 20.0).await;
 
         // Score on long goal
         _ = move_to.timeout(Duration::from_millis(1000)).move_to_point(dt, Vec2::new(23.5, 42.25)).await;
-        _ = self.duck_bill.set_high();
+        self.duck_bill.extend();
         self.lever.score(LeverStage::Score(6.0, 9.0));
         sleep(Duration::from_millis(500)).await;
-        _ = self.match_loader.set_low();
+        self.match_loader.retract();
 
         // Wing
         _ = move_to.speed(0.4).move_to_point(dt, Vec2::new(33.5, 33.0)).await;
         _ = turn.tolerance(Angle::from_degrees(1.0)).speed(0.6).turn_to(dt, Angle::QUARTER_TURN).await;
-        _ = self.wing.toggle();
+        self.wing.toggle();
         _ = move_to.speed(0.5).move_to_point(dt, Vec2::new(33.5, 64.0)).await;
         dt.brake(BrakeMode::Hold);
         sleep_until(timer + Duration::from_secs(19)).await;
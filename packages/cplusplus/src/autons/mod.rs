@@ -5,17 +5,15 @@ use atum::{controllers::pid::Pid, localization::vec2::Vec2};
 pub const LINEAR_PID: Pid = Pid::new(0.08, 0.04, 0.009, 8.0);
 pub const ANGULAR_PID: Pid = Pid::new(2.175, 0.2, 0.25, 10.0);
 
+/// Nominal field size used to mirror red coordinates to the blue side.
+const FIELD_SIZE: f64 = 144.0;
+
 const RED_LEFT_LOADER: Vec2<f64> = Vec2::new(28.0, 2.5);
 const RED_RIGHT_LOADER: Vec2<f64> = Vec2::new(144.0 - 24.0, 2.5);
-const BLUE_LEFT_LOADER: Vec2<f64> = mirror_coordinate(RED_LEFT_LOADER);
-const BLUE_RIGHT_LOADER: Vec2<f64> = mirror_coordinate(RED_RIGHT_LOADER);
+const BLUE_LEFT_LOADER: Vec2<f64> = RED_LEFT_LOADER.mirrored(FIELD_SIZE);
+const BLUE_RIGHT_LOADER: Vec2<f64> = RED_RIGHT_LOADER.mirrored(FIELD_SIZE);
 
 const RED_LEFT_GOAL: Vec2<f64> = Vec2::new(26.0, 48.0);
 const RED_RIGHT_GOAL: Vec2<f64> = Vec2::new(144.0 - 25.0, 48.0);
-const BLUE_LEFT_GOAL: Vec2<f64> = mirror_coordinate(RED_LEFT_GOAL);
-const BLUE_RIGHT_GOAL: Vec2<f64> = mirror_coordinate(RED_RIGHT_GOAL);
-
-#[inline]
-const fn mirror_coordinate(coordinate: Vec2<f64>) -> Vec2<f64> {
-    Vec2::new(144.0 - coordinate.x, 144.0 - coordinate.y)
-}
+const BLUE_LEFT_GOAL: Vec2<f64> = RED_LEFT_GOAL.mirrored(FIELD_SIZE);
+const BLUE_RIGHT_GOAL: Vec2<f64> = RED_RIGHT_GOAL.mirrored(FIELD_SIZE);
@@ -66,11 +66,11 @@ impl Robot {
             },
             async {
                 // wait until the robot is 32 inches to lift the rake
-                while (target - self.pose.borrow().position()).length() > 32.0 {
+                while (target - self.pose.snapshot().position()).length() > 32.0 {
                     sleep(Duration::from_millis(10)).await;
                 }
 
-                _ = self.rake.set_low();
+                self.rake.extend();
             }
         ).await;
         dt.set_arcade(8.0, 0.0);
@@ -79,24 +79,24 @@ impl Robot {
 
         // align to the long goal
         _ = turn.tolerance(Angle::from_degrees(5.0)).turn_to_point(dt, Vec2::new(117.0, 40.0), false).await;
-        _ = self.lift.set_high();
+        self.lift.extend();
         self.intake.set_bottom(0.0);
         self.intake.set_top(12.0);
         _ = move_to.timeout(Duration::from_millis(1000)).move_to_point(dt, Vec2::new(118.0, 40.0)).await;
 
         // score one red block
-        _ = self.duck_bill.set_high();
+        self.duck_bill.extend();
         dt.set_arcade(3.0, 0.0);
         sleep(Duration::from_millis(225)).await;
         self.intake.set_voltage(0.0);
         sleep(Duration::from_millis(100)).await;
-        _ = self.duck_bill.set_low();
+        self.duck_bill.retract();
 
         // move away while reseting pnuematic postion
-        _ = self.rake.set_high();
-        _ = self.lift.set_low();
+        self.rake.retract();
+        self.lift.retract();
         _ = move_to.timeout(Duration::from_millis(1500)).move_to_point(dt, Vec2::new(86.0, 24.0)).await;
-        _ = self.rake.set_low();
+        self.rake.extend();
 
         // collect block in the middle
         _ = turn.speed(1.5).tolerance(Angle::from_degrees(10.0)).turn_to(dt, Angle::from_degrees(75.0)).await;
@@ -104,7 +104,7 @@ impl Robot {
         self.intake.set_bottom(12.0);
         _ = move_to.timeout(Duration::from_millis(1500)).move_to_point(dt, Vec2::new(91.0, 66.0)).await;
         _ = turn.speed(1.0).tolerance(Angle::from_degrees(1.0)).turn_to(dt, Angle::from_degrees(-95.0)).await;
-        // _ = self.lift.set_low();
+        // self.lift.retract();
 
         // align to the upper goal
         _ = move_to.timeout(Duration::from_millis(2000)).move_to_point(dt, Vec2::new(86.0, 86.0)).await;
@@ -112,7 +112,7 @@ impl Robot {
 
         // _ = turn.turn_to_point(dt, Vec2::new(71.0, 71.0), false).await;
         _ = turn.turn_to(dt, Angle::from_degrees(-135.0)).await;
-        _ = self.duck_bill.set_high();
+        self.duck_bill.extend();
         _ = linear.min_velocity(0.5).drive_distance(dt, 7.5).await;
         // _ = linear.drive_distance(dt, 7.5).await;
         sleep(Duration::from_millis(50)).await;
@@ -132,7 +132,7 @@ impl Robot {
         // collect wall balls
         _ = linear.speed(1.0).drive_distance(dt, -48.0).await;
         self.intake.set_top(0.0);
-        _ = self.duck_bill.set_low();
+        self.duck_bill.retract();
         _ = turn.turn_to(dt, Angle::ZERO).await;
         _ = linear.drive_distance(dt, 14.0).await;
         dt.set_arcade(8.0, 0.0);
@@ -146,8 +146,8 @@ impl Robot {
         sleep_until(start + Duration::from_secs(24)).await;
 
         _ = linear.speed(0.75).drive_distance(dt, 24.5).await;
-        _ = self.rake.set_high();
-        // _ = self.lift.set_low();
+        self.rake.retract();
+        // self.lift.retract();
         sleep(Duration::from_millis(325)).await;
 
         _ = linear.drive_distance(dt, -7.5).await;
@@ -160,14 +160,14 @@ impl Robot {
         _ = linear.drive_distance(dt, 20.0).await;
         let target = Vec2::new(50.0, 75.5);
         _ = turn.tolerance(Angle::from_degrees(10.0)).turn_to_point(dt, target, false).await;
-        _ = self.rake.set_low();
+        self.rake.extend();
         _ = move_to.move_to_point(dt, target).await;
         sleep(Duration::from_millis(400)).await;
         self.intake.set_bottom(0.0);
 
         // score in the lower goal
         _ = turn.tolerance(Angle::from_degrees(1.0)).turn_to_point(dt, Vec2::new(52.0, 90.0), true).await;
-        _ = self.lift.set_high();
+        self.lift.extend();
         // _ = move_to.speed(1.25).move_to_point(dt, Vec2::new(52.0, 90.0)).await;
         _ = move_to.move_to_point(dt, Vec2::new(50.0, 90.0)).await;
         self.intake.set_top(-12.0);
@@ -211,7 +211,7 @@ impl Robot {
         debug!("pitch {}", dt.odometry.pitch().as_degrees());
 
         sleep_until(start + Duration::from_secs(52)).await;
-        _ = self.lift.set_low();
+        self.lift.retract();
 
         sleep_until(start + Duration::from_mins(1)).await;
     }
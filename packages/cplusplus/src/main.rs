@@ -10,15 +10,18 @@ use atum::{
     backend::start_ui,
     controllers::pid::Pid,
     hardware::{
+        battery::Battery,
         imu::Imu,
         motor_group::{MotorController, MotorGroup},
+        pneumatic::{AirSupply, Pneumatic, log_diagnostics},
         tracking_wheel::TrackingWheel,
         wall_distance_sensor::WallDistanceSensor,
     },
     localization::{
         odometry::Odometry,
         pose::Pose,
-        rcl::{MAX_ERROR, RaycastLocalization},
+        pose_handle::PoseHandle,
+        rcl::{MAX_ERROR, RaycastLocalization, RclParameters},
         shape::Circle,
         vec2::Vec2,
     },
@@ -31,21 +34,35 @@ use atum::{
 };
 use lazy_static::lazy_static;
 use log::{LevelFilter, info};
-use vexide::{adi::digital::LogicLevel, math::Angle, prelude::*, smart::motor::BrakeMode};
+use vexide::{math::Angle, prelude::*, smart::motor::BrakeMode};
 
 use crate::autons::{ANGULAR_PID, LINEAR_PID};
 
+/// Actuations of a cylinder a full charge of the air tanks is good for.
+const AIR_CAPACITY: f64 = 40.0;
+
+/// Time between pneumatic diagnostics during driver control.
+const DIAGNOSTIC_PERIOD: Duration = Duration::from_secs(30);
+
 struct Robot {
     controller: Controller,
+    battery: Battery,
     drivetrain: Drivetrain,
     intake: Basic,
-    lift: AdiDigitalOut,
-    duck_bill: AdiDigitalOut,
-    rake: AdiDigitalOut,
-    pose: Rc<RefCell<Pose>>,
+    lift: Pneumatic,
+    duck_bill: Pneumatic,
+    rake: Pneumatic,
+    pose: PoseHandle,
     settings: Rc<RefCell<Settings>>,
 }
 
+impl Robot {
+    /// Returns every cylinder fed by the air tanks.
+    fn pneumatics(&self) -> [&Pneumatic; 3] {
+        [&self.lift, &self.duck_bill, &self.rake]
+    }
+}
+
 impl Compete for Robot {
     async fn autonomous(&mut self) {
         println!("autnomous");
@@ -58,12 +75,21 @@ impl Compete for Robot {
         }
 
         info!("Time elapsed: {:?}", time.elapsed());
+        log_diagnostics(self.pneumatics());
     }
 
     async fn driver(&mut self) {
         let mut brake = false;
+        let mut last_diagnostic = Instant::now();
 
         loop {
+            self.battery.warn_controller(&mut self.controller).await;
+
+            if last_diagnostic.elapsed() > DIAGNOSTIC_PERIOD {
+                log_diagnostics(self.pneumatics());
+                last_diagnostic = Instant::now();
+            }
+
             let state = self.controller.state().unwrap_or_default();
             let mappings = ControllerMappings {
                 drive_mode: DriveMode::Arcade {
@@ -90,6 +116,7 @@ impl Compete for Robot {
                 self.drivetrain.brake(BrakeMode::Hold);
             } else {
                 self.drivetrain.drive(&mappings.drive_mode);
+                self.drivetrain.check_health();
             }
 
             if mappings.intake.is_pressed() {
@@ -102,17 +129,17 @@ impl Compete for Robot {
             }
 
             if mappings.lift.is_now_pressed() {
-                _ = self.lift.toggle();
+                self.lift.toggle();
             }
 
             if mappings.wing.is_now_pressed() {
-                _ = self.rake.toggle();
+                self.rake.toggle();
             }
 
             if mappings.duck_bill.is_pressed() {
-                _ = self.duck_bill.set_high();
+                self.duck_bill.extend();
             } else {
-                _ = self.duck_bill.set_low();
+                self.duck_bill.retract();
             }
 
             if mappings.swap_color.is_now_pressed() {
@@ -238,7 +265,7 @@ async fn main(peripherals: Peripherals) {
 
     imu.calibrate().await;
 
-    let rcl = RaycastLocalization::new(
+    let mut rcl = RaycastLocalization::new(
         vec![
             WallDistanceSensor::new(
                 peripherals.port_20,
@@ -271,16 +298,20 @@ async fn main(peripherals: Peripherals) {
             Circle::new(Vec2::new(23.5, 138.045), 3.0),
             Circle::new(Vec2::new(116.92, 138.045), 3.0),
         ],
+        RclParameters {
+            max_error: 48.0,
+            ..Default::default()
+        },
     );
 
     let relative_position = Pose::new(70.2, 23.0, -Angle::QUARTER_TURN);
-    let corrected = rcl.corrected_pose(relative_position, 48.0);
-    let starting_position = Rc::new(RefCell::new(corrected));
+    let corrected = rcl.corrected_pose(relative_position);
+    rcl.set_max_error(MAX_ERROR);
+    let starting_position = PoseHandle::new(corrected);
     let cloned_pose = starting_position.clone();
     spawn(async move {
         loop {
-            let corrected = rcl.corrected_pose(*cloned_pose.borrow(), MAX_ERROR);
-            cloned_pose.replace(corrected);
+            rcl.apply(&cloned_pose);
             sleep(Duration::from_millis(30)).await;
         }
     })
@@ -300,8 +331,16 @@ async fn main(peripherals: Peripherals) {
         0.0,
     ));
 
+    let air = AirSupply::new(AIR_CAPACITY);
+    let pneumatic = |name, port, inverted| {
+        let mut pneumatic = Pneumatic::new(name, port, inverted);
+        pneumatic.set_air_supply(Some(air.clone()));
+        pneumatic
+    };
+
     let robot = Robot {
         controller: peripherals.primary_controller,
+        battery: Battery::new(),
         drivetrain: Drivetrain::new(
             MotorGroup::new(
                 vec![
@@ -321,7 +360,7 @@ async fn main(peripherals: Peripherals) {
                 ],
                 motor_controller,
             ),
-            Odometry::new(starting_position.clone(), wheel_1, wheel_2, imu),
+            Odometry::new(starting_position.clone(), vec![wheel_1, wheel_2], Some(imu)),
             2.5,
             12.0,
         ),
@@ -335,9 +374,10 @@ async fn main(peripherals: Peripherals) {
                 None,
             ),
         ),
-        lift: AdiDigitalOut::new(peripherals.adi_f),
-        duck_bill: AdiDigitalOut::new(peripherals.adi_g),
-        rake: AdiDigitalOut::with_initial_level(peripherals.adi_h, LogicLevel::High),
+        lift: pneumatic("lift", peripherals.adi_f, false),
+        duck_bill: pneumatic("duck bill", peripherals.adi_g, false),
+        // The rake is stowed with the valve high
+        rake: pneumatic("rake", peripherals.adi_h, true),
         pose: starting_position,
         settings: settings.clone(),
     };
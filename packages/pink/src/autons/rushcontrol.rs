@@ -69,7 +69,7 @@ impl Robot {
 
         // Grab balls from match loader
         self.intake.set_voltage(Motor::V5_MAX_VOLTAGE);
-        _ = self.match_loader.set_high();
+        self.match_loader.extend();
 
         _ = linear
             .timeout(Duration::from_millis(7500))
@@ -80,17 +80,17 @@ impl Robot {
         // Drive to goal
         _ = linear.drive_distance(dt, -7.5).await;
         self.intake.set_voltage(0.0);
-        _ = self.match_loader.set_low();
+        self.match_loader.retract();
         sleep(Duration::from_millis(500)).await; // wait for balls to settle in robot
-        _ = self.lift.set_high();
-        _ = self.wing.set_high();
+        self.lift.extend();
+        self.wing.retract();
 
         _ = turn.timeout(Duration::from_millis(1000))
             .turn_to_point(dt, RED_RIGHT_GOAL, false)
             .await;
 
         // Score on goal
-        _ = self.duck_bill.set_high();
+        self.duck_bill.extend();
         zip(
             async {
                 _ = move_to
@@ -101,7 +101,7 @@ impl Robot {
             async {
                 // sleep so robot gains velocity from driving
                 sleep(Duration::from_millis(250)).await;
-                while self.pose.borrow().vf > 1.0 {
+                while self.pose.snapshot().vf > 1.0 {
                     sleep(Duration::from_millis(10)).await;
                 }
                 self.intake.set_voltage(Motor::V5_MAX_VOLTAGE);
@@ -114,7 +114,7 @@ impl Robot {
         .await;
 
         // Back up and shove balls into goal
-        _ = self.duck_bill.set_low();
+        self.duck_bill.retract();
         _ = linear
             .timeout(Duration::from_millis(1000))
             .drive_distance(dt, -5.0)
@@ -131,7 +131,7 @@ impl Robot {
         _ = turn.timeout(Duration::from_millis(1500))
             .turn_to(dt, Angle::from_degrees(90.0))
             .await;
-        _ = self.wing.set_low();
+        self.wing.extend();
 
         _ = move_to
             .timeout(Duration::from_millis(1500))
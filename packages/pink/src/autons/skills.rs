@@ -70,7 +70,7 @@ impl Robot {
 
         // Grab balls from match loader
         self.intake.set_voltage(Motor::V5_MAX_VOLTAGE);
-        _ = self.match_loader.set_high();
+        self.match_loader.extend();
 
         _ = linear
             .timeout(Duration::from_millis(7500))
@@ -81,17 +81,17 @@ impl Robot {
         // Drive to goal
         _ = linear.drive_distance(dt, -7.5).await;
         self.intake.set_voltage(0.0);
-        _ = self.match_loader.set_low();
+        self.match_loader.retract();
         sleep(Duration::from_millis(500)).await; // wait for balls to settle in robot
-        _ = self.lift.set_high();
-        _ = self.wing.set_high();
+        self.lift.extend();
+        self.wing.retract();
 
         _ = turn.timeout(Duration::from_millis(1000))
             .turn_to_point(dt, RED_RIGHT_GOAL, false)
             .await;
 
         // Score on goal
-        _ = self.duck_bill.set_high();
+        self.duck_bill.extend();
         zip(
             async {
                 _ = move_to
@@ -102,7 +102,7 @@ impl Robot {
             async {
                 // sleep so robot gains velocity from driving
                 sleep(Duration::from_millis(250)).await;
-                while self.pose.borrow().vf > 1.0 {
+                while self.pose.snapshot().vf > 1.0 {
                     sleep(Duration::from_millis(10)).await;
                 }
                 self.intake.set_voltage(Motor::V5_MAX_VOLTAGE);
@@ -115,7 +115,7 @@ impl Robot {
         .await;
 
         // Back up and shove balls into goal
-        _ = self.duck_bill.set_low();
+        self.duck_bill.retract();
         _ = linear
             .timeout(Duration::from_millis(1000))
             .drive_distance(dt, -5.0)
@@ -129,7 +129,7 @@ impl Robot {
         _ = turn.timeout(Duration::from_millis(1500))
             .turn_to(dt, Angle::from_degrees(90.0))
             .await;
-        _ = self.wing.set_low();
+        self.wing.extend();
 
         _ = move_to
             .timeout(Duration::from_millis(1500))
@@ -137,27 +137,27 @@ impl Robot {
             .move_to_point(dt, Vec2::new(107.0, 55.0))
             .await;
 
-        _ = self.wing.set_high();
+        self.wing.retract();
 
         // drive to the second match load
-        let target = Vec2::new(self.pose.borrow().x, 144.0 - 24.0);
+        let target = Vec2::new(self.pose.snapshot().x, 144.0 - 24.0);
         _ = linear.drive_to_point(dt, target, false).await;
 
-        _ = self.lift.set_low();
-        _ = self.wing.set_low();
+        self.lift.retract();
+        self.wing.extend();
 
         _ = turn.turn_to(dt, Angle::ZERO).await;
-        let target = Vec2::new(144.0 - 24.0, self.pose.borrow().y);
+        let target = Vec2::new(144.0 - 24.0, self.pose.snapshot().y);
         _ = linear.drive_to_point(dt, target, false).await;
 
         _ = turn.turn_to(dt, Angle::from_degrees(90.0)).await;
-        _ = self.match_loader.set_high();
+        self.match_loader.extend();
         _ = linear.drive_distance(dt, 20.0).await;
 
         // MIRROR ON OTHER SIDE
         // grab balls and score on long goal
         self.intake.set_voltage(Motor::V5_MAX_VOLTAGE);
-        _ = self.match_loader.set_high();
+        self.match_loader.extend();
 
         _ = linear
             .timeout(Duration::from_millis(7500))
@@ -169,10 +169,10 @@ impl Robot {
         // Drive to goal
         _ = linear.drive_distance(dt, -7.5).await;
         self.intake.set_voltage(0.0);
-        _ = self.match_loader.set_low();
+        self.match_loader.retract();
         sleep(Duration::from_millis(500)).await; // wait for balls to settle in robot
-        _ = self.lift.set_high();
-        _ = self.wing.set_high();
+        self.lift.extend();
+        self.wing.retract();
 
         _ = turn.settle_velocity(10.0_f64.to_radians())
             .timeout(Duration::from_millis(1000))
@@ -180,7 +180,7 @@ impl Robot {
             .await;
 
         // Score on goal
-        _ = self.duck_bill.set_high();
+        self.duck_bill.extend();
         zip(
             async {
                 _ = move_to
@@ -191,7 +191,7 @@ impl Robot {
             async {
                 // sleep so robot gains velocity from driving
                 sleep(Duration::from_millis(250)).await;
-                while self.pose.borrow().vf > 1.0 {
+                while self.pose.snapshot().vf > 1.0 {
                     sleep(Duration::from_millis(10)).await;
                 }
                 self.intake.set_voltage(Motor::V5_MAX_VOLTAGE);
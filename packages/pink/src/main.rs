@@ -10,11 +10,13 @@ use atum::{
     backend::start_ui,
     controllers::pid::Pid,
     hardware::{
+        battery::Battery,
         imu::Imu,
         motor_group::{MotorController, MotorGroup},
+        pneumatic::{AirSupply, Pneumatic, log_diagnostics},
         tracking_wheel::TrackingWheel,
     },
-    localization::{odometry::Odometry, pose::Pose, vec2::Vec2},
+    localization::{odometry::Odometry, pose::Pose, pose_handle::PoseHandle, vec2::Vec2},
     logger::Logger,
     mappings::{ControllerMappings, DriveMode},
     settings::{Color, Settings},
@@ -25,18 +27,32 @@ use lazy_static::lazy_static;
 use log::{LevelFilter, info};
 use vexide::{math::Angle, prelude::*, smart::motor::BrakeMode};
 
+/// Actuations of a cylinder a full charge of the air tanks is good for.
+const AIR_CAPACITY: f64 = 40.0;
+
+/// Time between pneumatic diagnostics during driver control.
+const DIAGNOSTIC_PERIOD: Duration = Duration::from_secs(30);
+
 struct Robot {
     controller: Controller,
+    battery: Battery,
     drivetrain: Drivetrain,
     intake: Basic,
-    lift: AdiDigitalOut,
-    duck_bill: AdiDigitalOut,
-    match_loader: AdiDigitalOut,
-    wing: AdiDigitalOut,
-    pose: Rc<RefCell<Pose>>,
+    lift: Pneumatic,
+    duck_bill: Pneumatic,
+    match_loader: Pneumatic,
+    wing: Pneumatic,
+    pose: PoseHandle,
     settings: Rc<RefCell<Settings>>,
 }
 
+impl Robot {
+    /// Returns every cylinder fed by the air tanks.
+    fn pneumatics(&self) -> [&Pneumatic; 4] {
+        [&self.lift, &self.duck_bill, &self.match_loader, &self.wing]
+    }
+}
+
 impl Compete for Robot {
     async fn autonomous(&mut self) {
         let time = Instant::now();
@@ -49,12 +65,21 @@ impl Compete for Robot {
         }
 
         info!("Time elapsed: {:?}", time.elapsed());
+        log_diagnostics(self.pneumatics());
     }
 
     async fn driver(&mut self) {
         let mut brake = false;
+        let mut last_diagnostic = Instant::now();
 
         loop {
+            self.battery.warn_controller(&mut self.controller).await;
+
+            if last_diagnostic.elapsed() > DIAGNOSTIC_PERIOD {
+                log_diagnostics(self.pneumatics());
+                last_diagnostic = Instant::now();
+            }
+
             let state = self.controller.state().unwrap_or_default();
             let mappings = ControllerMappings {
                 drive_mode: DriveMode::Arcade {
@@ -81,6 +106,7 @@ impl Compete for Robot {
                 self.drivetrain.brake(BrakeMode::Hold);
             } else {
                 self.drivetrain.drive(&mappings.drive_mode);
+                self.drivetrain.check_health();
             }
 
             if mappings.intake.is_pressed() {
@@ -92,25 +118,26 @@ impl Compete for Robot {
             }
 
             if mappings.lift.is_now_pressed() {
-                _ = self.lift.toggle();
+                self.lift.toggle();
             }
 
             if mappings.duck_bill.is_pressed() {
-                _ = self.duck_bill.set_high();
+                self.duck_bill.extend();
             } else {
-                _ = self.duck_bill.set_low();
+                self.duck_bill.retract();
             }
 
             if mappings.match_load.is_now_pressed() {
-                _ = self.match_loader.toggle();
+                self.match_loader.toggle();
             }
 
+            // The wing tucks in while the lift is raised, unless held out
             if mappings.wing.is_pressed() {
-                _ = self.wing.set_low();
-            } else if self.lift.level().is_ok_and(|level| level.is_high()) {
-                _ = self.wing.set_high();
+                self.wing.extend();
+            } else if self.lift.is_extended() {
+                self.wing.retract();
             } else {
-                _ = self.wing.set_low();
+                self.wing.extend();
             }
 
             self.settings.borrow_mut().color_override = mappings.back_door.is_now_pressed();
@@ -168,7 +195,6 @@ async fn main(peripherals: Peripherals) {
         Angle::from_degrees(-45.0),
     );
 
-    // TODO - make imu calibrate at the same time
     let mut imu = Imu::new(vec![
         InertialSensor::new(peripherals.port_14),
         InertialSensor::new(peripherals.port_15),
@@ -178,7 +204,7 @@ async fn main(peripherals: Peripherals) {
     );
     imu.calibrate().await;
 
-    let starting_position = Rc::new(RefCell::new(Pose::default()));
+    let starting_position = PoseHandle::new(Pose::default());
 
     let settings = Rc::new(RefCell::new(Settings {
         color: Color::Red,
@@ -194,8 +220,16 @@ async fn main(peripherals: Peripherals) {
         0.0,
     ));
 
+    let air = AirSupply::new(AIR_CAPACITY);
+    let pneumatic = |name, port, inverted| {
+        let mut pneumatic = Pneumatic::new(name, port, inverted);
+        pneumatic.set_air_supply(Some(air.clone()));
+        pneumatic
+    };
+
     let robot = Robot {
         controller: peripherals.primary_controller,
+        battery: Battery::new(),
         drivetrain: Drivetrain::new(
             MotorGroup::new(
                 vec![
@@ -217,7 +251,7 @@ async fn main(peripherals: Peripherals) {
                 ],
                 motor_controller,
             ),
-            Odometry::new(starting_position.clone(), wheel_1, wheel_2, imu),
+            Odometry::new(starting_position.clone(), vec![wheel_1, wheel_2], Some(imu)),
             2.5,
             12.0,
         ),
@@ -225,10 +259,11 @@ async fn main(peripherals: Peripherals) {
             Motor::new(peripherals.port_4, Gearset::Blue, Direction::Forward),
             Motor::new(peripherals.port_5, Gearset::Blue, Direction::Reverse),
         ),
-        lift: AdiDigitalOut::new(peripherals.adi_g),
-        duck_bill: AdiDigitalOut::new(peripherals.adi_h),
-        match_loader: AdiDigitalOut::new(peripherals.adi_f),
-        wing: AdiDigitalOut::new(peripherals.adi_e),
+        lift: pneumatic("lift", peripherals.adi_g, false),
+        duck_bill: pneumatic("duck bill", peripherals.adi_h, false),
+        match_loader: pneumatic("match loader", peripherals.adi_f, false),
+        // The wing is tucked in with the valve high
+        wing: pneumatic("wing", peripherals.adi_e, true),
         pose: starting_position,
         settings: settings.clone(),
     };
@@ -7,8 +7,14 @@ use std::{
 use atum::{
     backend::start_ui,
     controllers::pid::Pid,
-    hardware::{imu::Imu, motor_group::MotorGroup, tracking_wheel::TrackingWheel},
-    localization::{odometry::Odometry, pose::Pose, vec2::Vec2},
+    hardware::{
+        battery::Battery,
+        imu::Imu,
+        motor_group::MotorGroup,
+        pneumatic::{AirSupply, Pneumatic, log_diagnostics},
+        tracking_wheel::TrackingWheel,
+    },
+    localization::{odometry::Odometry, pose::Pose, pose_handle::PoseHandle, vec2::Vec2},
     logger::Logger,
     mappings::{ControllerMappings, DriveMode},
     motion::{MotionError, MotionParameters, linear::Linear, move_to::MoveTo, turn::Turn},
@@ -19,19 +25,33 @@ use lazy_static::lazy_static;
 use log::{LevelFilter, debug, info};
 use vexide::{math::Angle, prelude::*, smart::motor::BrakeMode};
 
+/// Actuations of a cylinder a full charge of the air tanks is good for.
+const AIR_CAPACITY: f64 = 40.0;
+
+/// Time between pneumatic diagnostics during driver control.
+const DIAGNOSTIC_PERIOD: Duration = Duration::from_secs(30);
+
 struct Robot {
     controller: Controller,
+    battery: Battery,
     drivetrain: Drivetrain,
     intake: Basic,
-    lift: AdiDigitalOut,
-    duck_bill: AdiDigitalOut,
-    match_loader: AdiDigitalOut,
-    wing: AdiDigitalOut,
-    brake: AdiDigitalOut,
-    pose: Rc<RefCell<Pose>>,
+    lift: Pneumatic,
+    duck_bill: Pneumatic,
+    match_loader: Pneumatic,
+    wing: Pneumatic,
+    brake: Pneumatic,
+    pose: PoseHandle,
     settings: Rc<RefCell<Settings>>,
 }
 
+impl Robot {
+    /// Returns every cylinder fed by the air tanks.
+    fn pneumatics(&self) -> [&Pneumatic; 5] {
+        [&self.lift, &self.duck_bill, &self.match_loader, &self.wing, &self.brake]
+    }
+}
+
 impl Compete for Robot {
     async fn autonomous(&mut self) {
         let time = Instant::now();
@@ -73,12 +93,21 @@ impl Compete for Robot {
         // }
 
         info!("Time elapsed: {:?}", time.elapsed());
+        log_diagnostics(self.pneumatics());
     }
 
     async fn driver(&mut self) {
         let mut brake = false;
+        let mut last_diagnostic = Instant::now();
 
         loop {
+            self.battery.warn_controller(&mut self.controller).await;
+
+            if last_diagnostic.elapsed() > DIAGNOSTIC_PERIOD {
+                log_diagnostics(self.pneumatics());
+                last_diagnostic = Instant::now();
+            }
+
             let state = self.controller.state().unwrap_or_default();
             let mappings = ControllerMappings {
                 drive_mode: DriveMode::Arcade {
@@ -105,6 +134,7 @@ impl Compete for Robot {
                 self.drivetrain.brake(BrakeMode::Hold);
             } else {
                 self.drivetrain.drive(&mappings.drive_mode);
+                self.drivetrain.check_health();
             }
 
             if mappings.intake.is_pressed() {
@@ -116,27 +146,28 @@ impl Compete for Robot {
             }
 
             if mappings.duck_bill.is_pressed() {
-                _ = self.duck_bill.set_high();
+                self.duck_bill.extend();
             } else {
-                _ = self.duck_bill.set_low();
+                self.duck_bill.retract();
             }
 
             if mappings.match_load.is_now_pressed() {
-                _ = self.match_loader.toggle();
+                self.match_loader.toggle();
             }
 
+            // The wing tucks in while the lift is raised, unless held out
             if mappings.wing.is_pressed() {
-                _ = self.wing.set_low();
-            } else if self.lift.level().is_ok_and(|level| level.is_high()) {
-                _ = self.wing.set_high();
+                self.wing.extend();
+            } else if self.lift.is_extended() {
+                self.wing.retract();
             } else {
-                _ = self.wing.set_low();
+                self.wing.extend();
             }
 
             if mappings.brake.is_pressed() {
-                _ = self.brake.set_high();
+                self.brake.extend();
             } else {
-                _ = self.brake.set_low();
+                self.brake.retract();
             }
 
             if mappings.swap_color.is_now_pressed() {
@@ -243,7 +274,6 @@ async fn main(peripherals: Peripherals) {
         Angle::from_degrees(-45.0),
     );
 
-    // TODO - make imu calibrate at the same time
     let mut imu = Imu::new(
         vec![
             InertialSensor::new(peripherals.port_20),
@@ -286,7 +316,7 @@ async fn main(peripherals: Peripherals) {
 
     // let relative_position = Pose::new(77.0, 23.0, Angle::ZERO);
     // let corrected = rcl.corrected_pose(relative_position, f64::INFINITY);
-    // let starting_position = Rc::new(RefCell::new(corrected));
+    // let starting_position = PoseHandle::new(corrected);
     // let cloned_pose = starting_position.clone();
 
     // spawn(async move {
@@ -297,7 +327,7 @@ async fn main(peripherals: Peripherals) {
     //     }
     // })
     // .detach();
-    let starting_position = Rc::new(RefCell::new(Pose::default()));
+    let starting_position = PoseHandle::new(Pose::default());
 
     let settings = Rc::new(RefCell::new(Settings {
         color: Color::Red,
@@ -306,8 +336,16 @@ async fn main(peripherals: Peripherals) {
         color_override: false,
     }));
 
+    let air = AirSupply::new(AIR_CAPACITY);
+    let pneumatic = |name, port, inverted| {
+        let mut pneumatic = Pneumatic::new(name, port, inverted);
+        pneumatic.set_air_supply(Some(air.clone()));
+        pneumatic
+    };
+
     let robot = Robot {
         controller: peripherals.primary_controller,
+        battery: Battery::new(),
         drivetrain: Drivetrain::new(
             MotorGroup::new(
                 vec![
@@ -329,7 +367,7 @@ async fn main(peripherals: Peripherals) {
                 ],
                 None,
             ),
-            Odometry::new(starting_position.clone(), wheel_1, wheel_2, imu),
+            Odometry::new(starting_position.clone(), vec![wheel_1, wheel_2], Some(imu)),
             2.5,
             12.0,
         ),
@@ -337,11 +375,12 @@ async fn main(peripherals: Peripherals) {
             Motor::new(peripherals.port_16, Gearset::Blue, Direction::Forward),
             Motor::new(peripherals.port_17, Gearset::Blue, Direction::Reverse),
         ),
-        lift: AdiDigitalOut::new(peripherals.adi_g),
-        duck_bill: AdiDigitalOut::new(peripherals.adi_h),
-        match_loader: AdiDigitalOut::new(adi_expander.adi_a),
-        wing: AdiDigitalOut::new(peripherals.adi_e),
-        brake: AdiDigitalOut::new(adi_expander.adi_b),
+        lift: pneumatic("lift", peripherals.adi_g, false),
+        duck_bill: pneumatic("duck bill", peripherals.adi_h, false),
+        match_loader: pneumatic("match loader", adi_expander.adi_a, false),
+        // The wing is tucked in with the valve high
+        wing: pneumatic("wing", peripherals.adi_e, true),
+        brake: pneumatic("brake", adi_expander.adi_b, false),
         pose: starting_position,
         settings: settings.clone(),
     };
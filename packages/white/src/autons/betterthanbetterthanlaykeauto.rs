@@ -50,11 +50,11 @@ impl Robot {
         );
 
         let dt = &mut self.drivetrain;
-        _ = self.lift.set_high();
+        self.lift.extend();
         self.lever.set_intake(Motor::V5_MAX_VOLTAGE);
-        let mut target = Vec2::new(35.5, self.pose.borrow().y);
+        let mut target = Vec2::new(35.5, self.pose.snapshot().y);
         _ = linear.settle_velocity(7.5).timeout(Duration::from_millis(750)).speed(2.0).drive_to_point(dt, target, true).await;
-        _ = self.match_loader.set_high();
+        self.match_loader.extend();
         _ = turn.speed(2.0).min_velocity(1.0_f64.to_radians()).tolerance(Angle::from_degrees(3.0)).timeout(Duration::from_millis(600)).turn_to_point(dt, Vec2::new(23.5,12.0), true).await;
         _ = move_to.timeout(Duration::from_millis(975)).min_velocity(Some(0.5)).speed(1.0).move_to_point(dt, Vec2::new(23.5,11.0)).await;
 
@@ -65,10 +65,10 @@ impl Robot {
             },
             async {
                 sleep(Duration::from_millis(250)).await;
-                while self.pose.borrow().vf > 1.0 {
+                while self.pose.snapshot().vf > 1.0 {
                     sleep(Duration::from_millis(10)).await;
                 }
-                _ = self.duck_bill.set_high();
+                self.duck_bill.extend();
                 self.lever.score(LeverStage::Score(6.0, 6.0));
             },
         ).await;
@@ -54,7 +54,7 @@ impl Robot {
 
         self.lever.set_intake(Motor::V5_MAX_VOLTAGE);
 
-        _ = self.lift.set_high();
+        self.lift.extend();
 
 
         _ = move_to.move_to_point(dt, Vec2::new(7.1, 23.5)).await;
@@ -71,11 +71,11 @@ impl Robot {
         // sleep(Duration::from_millis(325)).await;
 
         
-        let target = Vec2::new(23.5, self.pose.borrow().y);
+        let target = Vec2::new(23.5, self.pose.snapshot().y);
 
         _ = linear.timeout(Duration::from_millis(1400)).speed(1.0).drive_to_point(dt, target, false).await;
         _ = turn.timeout(Duration::from_millis(1000)).turn_to_point(dt, Vec2::new(23.0,42.0), false).await;
-        _ = self.match_loader.set_high();
+        self.match_loader.extend();
         sleep(Duration::from_millis(300)).await;
 
         zip(
@@ -84,15 +84,15 @@ impl Robot {
             },
             async {
                 sleep(Duration::from_millis(250)).await;
-                while self.pose.borrow().vf > 1.0 {
+                while self.pose.snapshot().vf > 1.0 {
                     sleep(Duration::from_millis(10)).await;
                 }
-                _ = self.duck_bill.set_high();
+                self.duck_bill.extend();
                 self.lever.score(LeverStage::Score(4.0, 3.0));
             },
         ).await;
 
-        _ = self.duck_bill.set_low();
+        self.duck_bill.retract();
         _ = move_to.min_velocity(Some(0.5)).speed(0.4).move_to_point(dt, Vec2::new(23.0,11.5)).await;
         dt.brake(BrakeMode::Hold);
         sleep(Duration::from_millis(1500)).await;
@@ -102,19 +102,19 @@ impl Robot {
         _ = linear.speed(1.0).drive_distance(dt, 24.0).await;
         self.lever.set_intake(0.0);
         
-        _ = self.match_loader.set_low();
+        self.match_loader.retract();
         _ = turn.turn_to(dt, Angle::ZERO).await;
-        let mut target = Vec2::new(10.0, self.pose.borrow().y);
+        let mut target = Vec2::new(10.0, self.pose.snapshot().y);
         _ = linear.drive_to_point(dt, target, true).await;
         _ = turn.turn_to(dt, -Angle::QUARTER_TURN).await;
 
-        target = Vec2::new(self.pose.borrow().x, 105.0);
+        target = Vec2::new(self.pose.snapshot().x, 105.0);
         _ = linear.timeout(Duration::from_millis(1800)).speed(1.0).drive_to_point(dt, target, true).await;
         _ = turn.turn_to(dt, Angle::ZERO).await;
 
-        target = Vec2::new(23.0, self.pose.borrow().y);
+        target = Vec2::new(23.0, self.pose.snapshot().y);
         _ = linear.drive_to_point(dt, target, false).await;
-        _ = self.match_loader.set_high();
+        self.match_loader.extend();
         self.lever.set_intake(Motor::V5_MAX_VOLTAGE);
         _ = turn.turn_to_point(dt, Vec2::new(23.0, 95.0), false).await;
 
@@ -126,16 +126,16 @@ impl Robot {
             },
             async {
                 sleep(Duration::from_millis(250)).await;
-                while self.pose.borrow().vf > 1.0 {
+                while self.pose.snapshot().vf > 1.0 {
                     sleep(Duration::from_millis(10)).await;
                 }
-                _ = self.duck_bill.set_high();
+                self.duck_bill.extend();
                 self.lever.score(LeverStage::Score(5.0, 4.0));
             },
         ).await;
 
         //////// Scores the first 6 ^^^
-        _ = self.duck_bill.set_low();
+        self.duck_bill.retract();
         _ = move_to.speed(0.4).min_velocity(Some(0.5)).move_to_point(dt, Vec2::new(23.0,129.0)).await;
         dt.brake(BrakeMode::Hold);
         sleep(Duration::from_millis(1500)).await;
@@ -147,10 +147,10 @@ impl Robot {
             },
             async {
                 sleep(Duration::from_millis(250)).await;
-                while self.pose.borrow().vf > 0.5 {
+                while self.pose.snapshot().vf > 0.5 {
                     sleep(Duration::from_millis(10)).await;
                 }
-                _ = self.duck_bill.set_high();
+                self.duck_bill.extend();
                 self.lever.score(LeverStage::Score(3.5, 2.1));
             },
         ).await;
@@ -161,22 +161,22 @@ impl Robot {
 
         sleep(Duration::from_millis(300)).await;
         _ = linear.speed(1.0).drive_distance(dt, -18.0).await;
-        _ = self.duck_bill.set_low();
-        _ = self.match_loader.set_low();
+        self.duck_bill.retract();
+        self.match_loader.retract();
         _ = turn.speed(1.0).turn_to(dt, Angle::ZERO).await;
 
-        target = Vec2::new(8.0, self.pose.borrow().y);
+        target = Vec2::new(8.0, self.pose.snapshot().y);
 
         _ = linear.timeout(Duration::from_millis(1500)).drive_to_point(dt, target, true).await;
         _ = linear.speed(1.0).timeout(Duration::from_millis(500)).drive_distance(dt, 5.0).await;
         _ = linear.speed(1.0).timeout(Duration::from_millis(500)).drive_distance(dt, -8.0).await;
 
-        target = Vec2::new(116.0, self.pose.borrow().y);
+        target = Vec2::new(116.0, self.pose.snapshot().y);
 
         _ = linear.timeout(Duration::from_millis(2300)).drive_to_point(dt, target, false).await;
 
         _ = turn.turn_to_point(dt, Vec2::new(117.0, 95.0), false).await;
-        _ = self.match_loader.set_high();
+        self.match_loader.extend();
 
         zip(
             async {
@@ -184,14 +184,14 @@ impl Robot {
             },
             async {
                 sleep(Duration::from_millis(250)).await;
-                while self.pose.borrow().vf > 1.0 {
+                while self.pose.snapshot().vf > 1.0 {
                     sleep(Duration::from_millis(10)).await;
                 }
-                _ = self.duck_bill.set_high();
+                self.duck_bill.extend();
                 self.lever.score(LeverStage::Score(4.0, 3.0));
             },
         ).await;
-        _ = self.duck_bill.set_low();
+        self.duck_bill.retract();
 
         _ = move_to.min_velocity(Some(0.25)).speed(0.4).move_to_point(dt, Vec2::new(116.0, 129.0)).await;
         dt.brake(BrakeMode::Hold);
@@ -200,25 +200,25 @@ impl Robot {
     
 
         
-       target = Vec2::new(self.pose.borrow().x, 103.0);
+       target = Vec2::new(self.pose.snapshot().x, 103.0);
 
         _ = linear.speed(1.0).drive_to_point(dt, target, false).await;
-        _ = self.match_loader.set_low();
+        self.match_loader.retract();
         
         _ = turn.turn_to(dt, Angle::HALF_TURN).await;
-        target = Vec2::new(131.0, self.pose.borrow().y);
+        target = Vec2::new(131.0, self.pose.snapshot().y);
         _ = linear.drive_to_point(dt, target, true).await;
         _ = turn.turn_to(dt, Angle::QUARTER_TURN).await;
         
         _ = linear.timeout(Duration::from_millis(1800)).speed(1.0).drive_distance(dt, -67.0).await;
         _ = turn.turn_to(dt, Angle::HALF_TURN).await;
-        target = Vec2::new(117.0, self.pose.borrow().y);
+        target = Vec2::new(117.0, self.pose.snapshot().y);
 
         _ = linear.drive_to_point(dt, target, false).await;
 
         _ = turn.speed(1.0).turn_to_point(dt, Vec2::new(117.0, 48.0), false).await;
 
-        _ = self.match_loader.set_high();
+        self.match_loader.extend();
         zip(
             async {
                 _ = move_to.move_to_point(dt, Vec2::new(117.0, 48.0)).await;
@@ -226,14 +226,14 @@ impl Robot {
             },
             async {
                 sleep(Duration::from_millis(250)).await;
-                while self.pose.borrow().vf > 1.0 {
+                while self.pose.snapshot().vf > 1.0 {
                     sleep(Duration::from_millis(10)).await;
                 }
-                _ = self.duck_bill.set_high();
+                self.duck_bill.extend();
                 self.lever.score(LeverStage::Score(3.7, 3.7));
             },
         ).await;
-        _ = self.duck_bill.set_low();
+        self.duck_bill.retract();
         _ = move_to.min_velocity(Some(0.5)).speed(0.4).move_to_point(dt, Vec2::new(116.0,11.0)).await;
         sleep(Duration::from_millis(1750)).await;
          zip(
@@ -242,10 +242,10 @@ impl Robot {
             },
             async {
                 sleep(Duration::from_millis(250)).await;
-                while self.pose.borrow().vf > 1.0 {
+                while self.pose.snapshot().vf > 1.0 {
                     sleep(Duration::from_millis(10)).await;
                 }
-                _ = self.duck_bill.set_high();
+                self.duck_bill.extend();
                 self.lever.score(LeverStage::Score(3.5, 2.1));
             },
         ).await;
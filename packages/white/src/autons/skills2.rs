@@ -55,7 +55,7 @@ impl Robot {
 
         self.lever.set_intake(Motor::V5_MAX_VOLTAGE);
 
-        _ = self.lift.set_high();
+        self.lift.extend();
 
 
         _ = move_to.move_to_point(dt, Vec2::new(7.1, 23.5)).await;
@@ -64,11 +64,11 @@ impl Robot {
         _ = linear.speed(1.0).timeout(Duration::from_millis(500)).drive_distance(dt, -10.0).await;
 
         
-        let mut target = Vec2::new(23.5, self.pose.borrow().y);
+        let mut target = Vec2::new(23.5, self.pose.snapshot().y);
 
         _ = linear.timeout(Duration::from_millis(1400)).speed(1.0).drive_to_point(dt, target, false).await;
         _ = turn.timeout(Duration::from_millis(750)).turn_to_point(dt, Vec2::new(23.0,42.0), false).await;
-        _ = self.match_loader.set_high();
+        self.match_loader.extend();
         sleep(Duration::from_millis(300)).await;
 
         zip(
@@ -77,15 +77,15 @@ impl Robot {
             },
             async {
                 sleep(Duration::from_millis(250)).await;
-                while self.pose.borrow().vf > 1.0 {
+                while self.pose.snapshot().vf > 1.0 {
                     sleep(Duration::from_millis(10)).await;
                 }
-                _ = self.duck_bill.set_high();
+                self.duck_bill.extend();
                 self.lever.score(LeverStage::Score(4.0, 3.0));
             },
         ).await;
 
-        _ = self.duck_bill.set_low();
+        self.duck_bill.retract();
         self.lever.set_intake(Motor::V5_MAX_VOLTAGE);
 
         _ = move_to.min_velocity(Some(0.5)).speed(0.45).move_to_point(dt, Vec2::new(23.0,12.75)).await;
@@ -97,13 +97,13 @@ impl Robot {
         sleep(Duration::from_millis(2000)).await;
         
         ////////////// Grabs first 6 balls ^^
-        _ = self.match_loader.set_low();
+        self.match_loader.retract();
         _ = move_to.tolerance(15.0).speed(1.0).move_to_point(dt, Vec2::new(7.5, 54.5)).await;
         self.lever.set_intake(0.0);
         _ = move_to.speed(0.8).timeout(Duration::from_millis(2500)).tolerance(7.5).move_to_point(dt, Vec2::new(12.0, 95.0)).await;
         _ = move_to.timeout(Duration::from_millis(1500)).speed(0.4).tolerance(5.0).move_to_point(dt, Vec2::new(23.5, 110.0)).await;
         _ = turn.turn_to_point(dt, Vec2::new(23.5, 95.0), false).await;
-        _ = self.match_loader.set_high();
+        self.match_loader.extend();
         self.lever.set_intake(Motor::V5_MAX_VOLTAGE);
         zip(
             async {
@@ -112,17 +112,17 @@ impl Robot {
             },
             async {
                 sleep(Duration::from_millis(250)).await;
-                while self.pose.borrow().vf > 0.5 {
+                while self.pose.snapshot().vf > 0.5 {
                     sleep(Duration::from_millis(10)).await;
                 }
-                _ = self.duck_bill.set_high();
+                self.duck_bill.extend();
                 self.lever.score(LeverStage::Score(4.0, 4.0));
             },
         ).await;
 
 
 
-        _ = self.duck_bill.set_low();
+        self.duck_bill.retract();
         _ = move_to.speed(0.5).min_velocity(Some(0.25)).move_to_point(dt, Vec2::new(23.5,127.5)).await;
         // dt.brake(BrakeMode::Hold);
         dt.set_arcade(-0.35, 0.0);
@@ -137,33 +137,33 @@ impl Robot {
             },
             async {
                 sleep(Duration::from_millis(250)).await;
-                while self.pose.borrow().vf > 0.5 {
+                while self.pose.snapshot().vf > 0.5 {
                     sleep(Duration::from_millis(10)).await;
                 }
-                _ = self.duck_bill.set_high();
+                self.duck_bill.extend();
                 self.lever.score(LeverStage::Score(3.5, 2.1));
                 sleep(Duration::from_millis(100)).await;
             },
         ).await;
         _ = linear.speed(1.0).drive_distance(dt, -18.5).await;
-        _ = self.duck_bill.set_low();
-        _ = self.match_loader.set_low();
+        self.duck_bill.retract();
+        self.match_loader.retract();
         _ = turn.speed(1.0).turn_to(dt, Angle::ZERO).await;
         self.lever.set_intake(Motor::V5_MAX_VOLTAGE);
 
-        target = Vec2::new(8.0, self.pose.borrow().y);
+        target = Vec2::new(8.0, self.pose.snapshot().y);
 
         _ = linear.timeout(Duration::from_millis(1500)).drive_to_point(dt, target, true).await;
         _ = linear.speed(1.0).timeout(Duration::from_millis(350)).drive_distance(dt, 5.0).await;
         _ = linear.speed(1.0).timeout(Duration::from_millis(400)).drive_distance(dt, -10.0).await;
         sleep_until(start + Duration::from_secs(22)).await;
 
-        target = Vec2::new(116.0, self.pose.borrow().y);
+        target = Vec2::new(116.0, self.pose.snapshot().y);
 
         _ = linear.timeout(Duration::from_millis(2300)).drive_to_point(dt, target, false).await;
 
         _ = turn.turn_to_point(dt, Vec2::new(117.0, 95.0), false).await;
-        _ = self.match_loader.set_high();
+        self.match_loader.extend();
 
         zip(
             async {
@@ -173,21 +173,21 @@ impl Robot {
             },
             async {
                 sleep(Duration::from_millis(250)).await;
-                while self.pose.borrow().vf > 1.0 {
+                while self.pose.snapshot().vf > 1.0 {
                     sleep(Duration::from_millis(10)).await;
                 }
-                _ = self.duck_bill.set_high();
+                self.duck_bill.extend();
                 self.lever.score(LeverStage::Score(4.0, 3.0));
             },
         ).await;
-        _ = self.duck_bill.set_low();
+        self.duck_bill.retract();
         self.lever.set_intake(Motor::V5_MAX_VOLTAGE);
         _ = move_to.min_velocity(Some(0.25)).speed(0.4).move_to_point(dt, Vec2::new(116.0, 127.75)).await;
         // dt.brake(BrakeMode::Hold);
         dt.set_arcade(-0.35, 0.0);
     
         sleep(Duration::from_millis(1700)).await;
-        _ = self.match_loader.set_low();
+        self.match_loader.retract();
 
         _ = move_to.tolerance(5.0).speed(1.0).move_to_point(dt, Vec2::new(100.0, 80.0)).await;
         self.lever.set_intake(0.0);
@@ -207,7 +207,7 @@ impl Robot {
 
         _ = turn.speed(1.0).turn_to_point(dt, Vec2::new(117.0, 48.0), false).await;
 
-        _ = self.match_loader.set_high();
+        self.match_loader.extend();
         zip(
             async {
                 _ = move_to.min_velocity(None).timeout(Duration::from_millis(2000)).speed(0.5).move_to_point(dt, Vec2::new(117.5, 48.0)).await;
@@ -216,16 +216,16 @@ impl Robot {
             },
             async {
                 sleep(Duration::from_millis(250)).await;
-                while self.pose.borrow().vf > 1.0 {
+                while self.pose.snapshot().vf > 1.0 {
                     sleep(Duration::from_millis(10)).await;
                 }
-                _ = self.duck_bill.set_high();
+                self.duck_bill.extend();
                 self.lever.score(LeverStage::Score(5.0, 4.0));
                 
 
             },
         ).await;
-        _ = self.duck_bill.set_low();
+        self.duck_bill.retract();
         _ = move_to.min_velocity(Some(0.5)).speed(0.4).move_to_point(dt, Vec2::new(117.5 ,12.75)).await;
         dt.set_arcade(-0.35, 0.0);
         sleep(Duration::from_millis(1750)).await;
@@ -237,15 +237,15 @@ impl Robot {
             },
             async {
                 sleep(Duration::from_millis(250)).await;
-                while self.pose.borrow().vf > 1.0 {
+                while self.pose.snapshot().vf > 1.0 {
                     sleep(Duration::from_millis(10)).await;
                 }
-                _ = self.duck_bill.set_high();
+                self.duck_bill.extend();
                 self.lever.score(LeverStage::Score(3.5, 2.1));
                 sleep(Duration::from_millis(100)).await;
             },
         ).await;
-        _ = self.match_loader.set_low();
+        self.match_loader.retract();
         self.lever.set_intake(Motor::V5_MAX_VOLTAGE);
         dt.set_arcade(-4.0, 0.0);
         sleep(Duration::from_millis(250)).await;
@@ -10,15 +10,18 @@ use atum::{
     backend::start_ui,
     controllers::pid::Pid,
     hardware::{
+        battery::Battery,
         imu::Imu,
         motor_group::{MotorController, MotorGroup},
+        pneumatic::{AirSupply, Pneumatic, log_diagnostics},
         tracking_wheel::TrackingWheel,
         wall_distance_sensor::WallDistanceSensor,
     },
     localization::{
         odometry::Odometry,
         pose::Pose,
-        rcl::{MAX_ERROR, RaycastLocalization},
+        pose_handle::PoseHandle,
+        rcl::{MAX_ERROR, RaycastLocalization, RclParameters},
         shape::Circle,
         vec2::Vec2,
     },
@@ -39,20 +42,34 @@ use atum::{
 };
 use lazy_static::lazy_static;
 use log::{LevelFilter, info};
-use vexide::{adi::digital::LogicLevel, math::Angle, prelude::*};
+use vexide::{math::Angle, prelude::*};
+
+/// Actuations of a cylinder a full charge of the air tanks is good for.
+const AIR_CAPACITY: f64 = 40.0;
+
+/// Time between pneumatic diagnostics during driver control.
+const DIAGNOSTIC_PERIOD: Duration = Duration::from_secs(30);
 
 struct Robot {
     controller: Controller,
+    battery: Battery,
     drivetrain: Drivetrain,
     lever: Lever,
-    lift: AdiDigitalOut,
-    duck_bill: AdiDigitalOut,
-    match_loader: AdiDigitalOut,
-    wing: AdiDigitalOut,
-    pose: Rc<RefCell<Pose>>,
+    lift: Pneumatic,
+    duck_bill: Pneumatic,
+    match_loader: Pneumatic,
+    wing: Pneumatic,
+    pose: PoseHandle,
     settings: Rc<RefCell<Settings>>,
 }
 
+impl Robot {
+    /// Returns every cylinder fed by the air tanks.
+    fn pneumatics(&self) -> [&Pneumatic; 4] {
+        [&self.lift, &self.duck_bill, &self.match_loader, &self.wing]
+    }
+}
+
 impl Compete for Robot {
     async fn autonomous(&mut self) {
         let time = Instant::now();
@@ -67,6 +84,7 @@ impl Compete for Robot {
         }
 
         info!("Time elapsed: {:?}", time.elapsed());
+        log_diagnostics(self.pneumatics());
     }
 
     async fn driver(&mut self) {
@@ -76,7 +94,16 @@ impl Compete for Robot {
 
         _ = self.controller.set_text(format!("Upper Stage"), 1, 1).await;
 
+        let mut last_diagnostic = Instant::now();
+
         loop {
+            self.battery.warn_controller(&mut self.controller).await;
+
+            if last_diagnostic.elapsed() > DIAGNOSTIC_PERIOD {
+                log_diagnostics(self.pneumatics());
+                last_diagnostic = Instant::now();
+            }
+
             let state = self.controller.state().unwrap_or_default();
             let mappings = ControllerMappingsLever {
                 drive_mode: DriveMode::Arcade {
@@ -95,6 +122,7 @@ impl Compete for Robot {
             };
 
             self.drivetrain.drive(&mappings.drive_mode);
+            self.drivetrain.check_health();
 
             if mappings.intake.is_pressed() {
                 self.lever.set_intake(Motor::V5_MAX_VOLTAGE);
@@ -120,20 +148,20 @@ impl Compete for Robot {
             }
 
             match self.lever.stage() {
-                LeverStage::Score(..) => _ = self.duck_bill.set_high(),
-                LeverStage::Reset => (),
+                LeverStage::Score(..) => self.duck_bill.extend(),
+                LeverStage::Reset | LeverStage::Hold(_) => (),
                 LeverStage::Idle => {
                     if open_bill {
-                        _ = self.duck_bill.set_high();
+                        self.duck_bill.extend();
                     } else if !mappings.lever.is_pressed() {
-                        _ = self.duck_bill.set_low();
+                        self.duck_bill.retract();
                     }
                 }
             }
 
             if mappings.lever.is_now_pressed() {
                 let lever_stage = match self.lever.stage() {
-                    LeverStage::Score(..) => LeverStage::Reset,
+                    LeverStage::Score(..) | LeverStage::Hold(_) => LeverStage::Reset,
                     LeverStage::Reset => LeverStage::Idle,
                     LeverStage::Idle => scoring[selected],
                 };
@@ -142,17 +170,17 @@ impl Compete for Robot {
             }
 
             if mappings.lift.is_now_pressed() {
-                _ = self.lift.toggle();
+                self.lift.toggle();
             }
 
             if mappings.wing.is_pressed() {
-                _ = self.wing.set_high();
+                self.wing.extend();
             } else {
-                _ = self.wing.set_low();
+                self.wing.retract();
             }
 
             if mappings.match_load.is_now_pressed() {
-                _ = self.match_loader.toggle();
+                self.match_loader.toggle();
             }
 
             if self.settings.borrow().test_auton {
@@ -218,7 +246,6 @@ async fn main(peripherals: Peripherals) {
         Angle::from_degrees(-45.0),
     );
 
-    // TODO - make imu calibrate at the same time
     let mut imu = Imu::new(
         vec![
             InertialSensor::new(peripherals.port_5),
@@ -228,7 +255,7 @@ async fn main(peripherals: Peripherals) {
     );
     imu.calibrate().await;
 
-    let rcl = RaycastLocalization::new(
+    let mut rcl = RaycastLocalization::new(
         vec![
             WallDistanceSensor::new(
                 peripherals.port_3,
@@ -261,17 +288,21 @@ async fn main(peripherals: Peripherals) {
             Circle::new(Vec2::new(23.5, 138.045), 3.0),
             Circle::new(Vec2::new(116.92, 138.045), 3.0),
         ],
+        RclParameters {
+            max_error: 24.0,
+            ..Default::default()
+        },
     );
 
     let relative_position = Pose::new(56.0, 21.5, Angle::ZERO);
-    let corrected = rcl.corrected_pose(relative_position, 24.0);
-    let starting_position = Rc::new(RefCell::new(corrected));
+    let corrected = rcl.corrected_pose(relative_position);
+    rcl.set_max_error(MAX_ERROR);
+    let starting_position = PoseHandle::new(corrected);
     let cloned_pose = starting_position.clone();
     spawn(async move {
         loop {
-            let corrected = rcl.corrected_pose(*cloned_pose.borrow(), MAX_ERROR);
-            cloned_pose.replace(corrected);
-            info!("{}", corrected);
+            rcl.apply(&cloned_pose);
+            info!("Drivetrain: {}", cloned_pose.snapshot());
 
             sleep(Duration::from_millis(30)).await;
         }
@@ -292,8 +323,16 @@ async fn main(peripherals: Peripherals) {
         0.0,
     ));
 
+    let air = AirSupply::new(AIR_CAPACITY);
+    let pneumatic = |name, port, inverted| {
+        let mut pneumatic = Pneumatic::new(name, port, inverted);
+        pneumatic.set_air_supply(Some(air.clone()));
+        pneumatic
+    };
+
     let robot = Robot {
         controller: peripherals.primary_controller,
+        battery: Battery::new(),
         drivetrain: Drivetrain::new(
             MotorGroup::new(
                 vec![
@@ -315,7 +354,7 @@ async fn main(peripherals: Peripherals) {
                 ],
                 motor_controller,
             ),
-            Odometry::new(starting_position.clone(), wheel_1, wheel_2, imu),
+            Odometry::new(starting_position.clone(), vec![wheel_1, wheel_2], Some(imu)),
             2.5,
             12.0,
         ),
@@ -330,10 +369,10 @@ async fn main(peripherals: Peripherals) {
             ),
             RotationSensor::new(peripherals.port_7, Direction::Forward),
         ),
-        lift: AdiDigitalOut::with_initial_level(peripherals.adi_a, LogicLevel::Low),
-        duck_bill: AdiDigitalOut::with_initial_level(peripherals.adi_d, LogicLevel::Low),
-        match_loader: AdiDigitalOut::with_initial_level(peripherals.adi_c, LogicLevel::Low),
-        wing: AdiDigitalOut::with_initial_level(peripherals.adi_b, LogicLevel::Low),
+        lift: pneumatic("lift", peripherals.adi_a, false),
+        duck_bill: pneumatic("duck bill", peripherals.adi_d, false),
+        match_loader: pneumatic("match loader", peripherals.adi_c, false),
+        wing: pneumatic("wing", peripherals.adi_b, false),
         pose: starting_position,
         settings: settings.clone(),
     };